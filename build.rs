@@ -0,0 +1,47 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+//writes the list of files for the embedded-assets feature, with it off the list is empty
+//so the game only ever reads from the assets folder
+fn main() {
+    println!("cargo:rerun-if-changed=assets");
+    let mut files = Vec::new();
+    if env::var_os("CARGO_FEATURE_EMBEDDED_ASSETS").is_some() {
+        collect(Path::new("assets"), &mut files);
+    }
+    files.sort();
+
+    let root = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let entries: String = files
+        .iter()
+        .map(|file| {
+            let name = file
+                .strip_prefix("assets")
+                .unwrap()
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            format!("    ({:?}, include_bytes!({:?})),\n", name, root.join(file))
+        })
+        .collect();
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("embedded_assets.rs");
+    fs::write(
+        out,
+        format!("pub static FILES: &[(&str, &[u8])] = &[\n{}];\n", entries),
+    )
+    .unwrap();
+}
+
+fn collect(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
@@ -0,0 +1,258 @@
+//Headless batch evaluation
+//Runs a controller (built in bot, trained genome or replay) over a range of seeds and prints
+//score, survival time and death cause stats as text or json so bots and difficulties can be compared
+use flappybird::{
+    network::Network,
+    sim::{
+        controller::{play, Autopilot, Controller, ReplayController, RunResult},
+        replay::Replay,
+        Difficulty, Mutators, TICK_RATE,
+    },
+};
+use serde::Serialize;
+use std::{collections::BTreeMap, env, process, thread};
+
+enum Pilot {
+    Bot,
+    Genome(String, Network),
+    Replay(String, Replay),
+}
+
+impl Pilot {
+    fn name(&self) -> String {
+        match self {
+            Pilot::Bot => "bot".to_string(),
+            Pilot::Genome(path, _) => format!("genome {}", path),
+            Pilot::Replay(path, _) => format!("replay {}", path),
+        }
+    }
+
+    //controllers keep state during a run so every run gets a fresh one
+    fn controller(&self) -> Box<dyn Controller> {
+        match self {
+            Pilot::Bot => Box::new(Autopilot),
+            Pilot::Genome(_, network) => Box::new(network.clone()),
+            Pilot::Replay(_, replay) => Box::new(ReplayController::new(replay)),
+        }
+    }
+}
+
+struct Options {
+    pilot: Pilot,
+    seeds: u64,
+    first_seed: u64,
+    difficulty: Difficulty,
+    mutators: Mutators,
+    max_seconds: u64,
+    json: bool,
+}
+
+const USAGE: &str = "usage: evaluate [--bot | --genome FILE | --replay FILE] [--seeds N] [--first-seed N] [--difficulty easy|normal|hard] [--mutators a,b] [--max-seconds N] [--json]";
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        pilot: Pilot::Bot,
+        seeds: 100,
+        first_seed: 0,
+        difficulty: Difficulty::Normal,
+        mutators: Mutators::NONE,
+        max_seconds: 300,
+        json: false,
+    };
+    let mut replay_seed = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--bot" => options.pilot = Pilot::Bot,
+            "--genome" => {
+                let path = value()?;
+                let network = Network::load(&path)
+                    .map_err(|err| format!("couldn't load genome {}: {}", path, err))?;
+                options.pilot = Pilot::Genome(path, network);
+            }
+            "--replay" => {
+                let path = value()?;
+                let replay = Replay::load(&path)
+                    .map_err(|err| format!("couldn't load replay {}: {}", path, err))?;
+                replay_seed = Some((replay.seed, replay.difficulty, replay.mutators));
+                options.pilot = Pilot::Replay(path, replay);
+            }
+            "--seeds" => options.seeds = parse(&value()?)?,
+            "--first-seed" => options.first_seed = parse(&value()?)?,
+            "--difficulty" => {
+                let name = value()?;
+                options.difficulty =
+                    Difficulty::from_name(&name).ok_or(format!("unknown difficulty {}", name))?;
+            }
+            "--mutators" => options.mutators = Mutators::from_names(&value()?)?,
+            "--max-seconds" => options.max_seconds = parse(&value()?)?,
+            "--json" => options.json = true,
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+    //a replay on its own just plays back its own run unless asked otherwise
+    if let Some((seed, difficulty, mutators)) = replay_seed {
+        if !env::args().any(|arg| arg == "--seeds" || arg == "--first-seed") {
+            options.first_seed = seed;
+            options.seeds = 1;
+        }
+        if !env::args().any(|arg| arg == "--difficulty") {
+            options.difficulty = difficulty;
+        }
+        if !env::args().any(|arg| arg == "--mutators") {
+            options.mutators = mutators;
+        }
+    }
+    if options.seeds == 0 {
+        return Err("need at least one seed".to_string());
+    }
+    Ok(options)
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} isn't a valid number", value))
+}
+
+#[derive(Serialize)]
+struct Stats {
+    mean: f64,
+    median: f64,
+    max: f64,
+}
+
+impl Stats {
+    fn new(mut values: Vec<f64>) -> Self {
+        values.sort_by(f64::total_cmp);
+        let middle = values.len() / 2;
+        let median = if values.len().is_multiple_of(2) {
+            (values[middle - 1] + values[middle]) / 2.
+        } else {
+            values[middle]
+        };
+        Stats {
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            median,
+            max: values[values.len() - 1],
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Report {
+    controller: String,
+    difficulty: Difficulty,
+    mutators: Vec<&'static str>,
+    first_seed: u64,
+    seeds: u64,
+    score: Stats,
+    survival_seconds: Stats,
+    //how many runs ended each way, "survived" means the run hit --max-seconds
+    deaths: BTreeMap<String, u64>,
+}
+
+impl Report {
+    fn new(options: &Options, results: &[RunResult]) -> Self {
+        let mut deaths = BTreeMap::new();
+        for result in results {
+            let cause = match result.death {
+                Some(cause) => format!("{:?}", cause).to_lowercase(),
+                None => "survived".to_string(),
+            };
+            *deaths.entry(cause).or_insert(0) += 1;
+        }
+        Report {
+            controller: options.pilot.name(),
+            difficulty: options.difficulty,
+            mutators: options.mutators.names(),
+            first_seed: options.first_seed,
+            seeds: options.seeds,
+            score: Stats::new(results.iter().map(|result| result.score as f64).collect()),
+            survival_seconds: Stats::new(
+                results
+                    .iter()
+                    .map(|result| result.ticks as f64 / TICK_RATE as f64)
+                    .collect(),
+            ),
+            deaths,
+        }
+    }
+
+    fn print_text(&self) {
+        let mutators = if self.mutators.is_empty() {
+            String::new()
+        } else {
+            format!(" with {}", self.mutators.join(", "))
+        };
+        println!(
+            "{} on {}{} over {} seeds from {}",
+            self.controller,
+            self.difficulty.name(),
+            mutators,
+            self.seeds,
+            self.first_seed
+        );
+        println!(
+            "score      mean {:>8.2}  median {:>8.2}  max {:>8.2}",
+            self.score.mean, self.score.median, self.score.max
+        );
+        println!(
+            "survival   mean {:>7.2}s  median {:>7.2}s  max {:>7.2}s",
+            self.survival_seconds.mean, self.survival_seconds.median, self.survival_seconds.max
+        );
+        println!("deaths");
+        for (cause, count) in self.deaths.iter() {
+            println!(
+                "  {:<10} {:>6}  ({:.1}%)",
+                cause,
+                count,
+                *count as f64 * 100. / self.seeds as f64
+            );
+        }
+    }
+}
+
+fn main() {
+    let options = parse_options().unwrap_or_else(|err| {
+        eprintln!("{}\n{}", err, USAGE);
+        process::exit(2);
+    });
+    let rules = options.mutators.rules(options.difficulty.rules());
+    let max_ticks = options.max_seconds * TICK_RATE as u64;
+    let seeds: Vec<u64> = (0..options.seeds)
+        .map(|seed| options.first_seed.wrapping_add(seed))
+        .collect();
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let chunk = seeds.len().div_ceil(threads);
+
+    let results: Vec<RunResult> = thread::scope(|scope| {
+        let pilot = &options.pilot;
+        let handles: Vec<_> = seeds
+            .chunks(chunk)
+            .map(|seeds| {
+                scope.spawn(move || {
+                    seeds
+                        .iter()
+                        .map(|seed| play(rules, *seed, pilot.controller().as_mut(), max_ticks))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("evaluation thread panicked"))
+            .collect()
+    });
+
+    let report = Report::new(&options, &results);
+    if options.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("report always serializes")
+        );
+    } else {
+        report.print_text();
+    }
+}
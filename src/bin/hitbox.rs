@@ -0,0 +1,93 @@
+//Traces the outline of a sprite for traced hitboxes
+//Prints the convex hull of every pixel that's opaque enough as json, in the picture's pixels
+//from its middle with y up, ready to go in a theme's "hull" for the bird or pipe
+use bevy::render::{
+    render_asset::RenderAssetUsages,
+    render_resource::TextureFormat,
+    texture::{CompressedImageFormats, Image, ImageSampler, ImageType},
+};
+use flappybird::sim::hitbox::Hull;
+use std::{env, fs, path::Path, process};
+
+struct Options {
+    image: String,
+    threshold: u8,
+    inset: f32,
+}
+
+const USAGE: &str = "usage: hitbox IMAGE [--threshold 0-255] [--inset PIXELS]";
+
+fn parse_options() -> Result<Options, String> {
+    let mut image = None;
+    let mut threshold = 128;
+    let mut inset = 0.;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--threshold" => {
+                let value = args.next().ok_or("--threshold needs a value")?;
+                threshold = value
+                    .parse()
+                    .map_err(|_| format!("{} isn't from 0 to 255", value))?;
+            }
+            "--inset" => {
+                let value = args.next().ok_or("--inset needs a value")?;
+                inset = value
+                    .parse()
+                    .map_err(|_| format!("{} isn't a valid number", value))?;
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown argument {}", arg)),
+            _ if image.is_none() => image = Some(arg),
+            _ => return Err(format!("only one image at a time, got {} too", arg)),
+        }
+    }
+    Ok(Options {
+        image: image.ok_or("need an image to trace")?,
+        threshold,
+        inset,
+    })
+}
+
+fn trace(options: &Options) -> Result<Hull, String> {
+    let bytes = fs::read(&options.image).map_err(|err| err.to_string())?;
+    let extension = Path::new(&options.image)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("png");
+    let image = Image::from_buffer(
+        &bytes,
+        ImageType::Extension(extension),
+        CompressedImageFormats::NONE,
+        true,
+        ImageSampler::Default,
+        RenderAssetUsages::default(),
+    )
+    .map_err(|err| err.to_string())?
+    .convert(TextureFormat::Rgba8UnormSrgb)
+    .ok_or("it isn't a picture format that has alpha")?;
+    let alpha: Vec<u8> = image.data.chunks(4).map(|pixel| pixel[3]).collect();
+    Hull::from_alpha(
+        image.width(),
+        image.height(),
+        &alpha,
+        options.threshold,
+        options.inset,
+    )
+}
+
+fn main() {
+    let options = parse_options().unwrap_or_else(|err| {
+        eprintln!("{}\n{}", err, USAGE);
+        process::exit(2);
+    });
+    match trace(&options) {
+        Ok(hull) => println!(
+            "{}",
+            serde_json::to_string(&hull).expect("hull always serializes")
+        ),
+        Err(err) => {
+            eprintln!("couldn't trace {}: {}", options.image, err);
+            process::exit(1);
+        }
+    }
+}
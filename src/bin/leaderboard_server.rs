@@ -0,0 +1,253 @@
+//Stand in for the online leaderboard
+//A tiny single threaded http server that speaks the same api as the real one so submitting,
+//the offline queue and the main menu's top scores can all be tried out locally.
+//  GET  /scores?limit=N   best scores first as json
+//  POST /scores           a leaderboard::Submission as json, the replay has to verify
+use flappybird::{
+    leaderboard::{self, Entry, Submission},
+    sim::{replay::Replay, verify::verify},
+};
+use std::{
+    collections::HashSet,
+    env, fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    process,
+    time::Duration,
+};
+
+struct Options {
+    port: u16,
+    //scores are kept here between runs if set, in memory only otherwise
+    file: Option<String>,
+}
+
+const USAGE: &str = "usage: leaderboard_server [--port N] [--file FILE]";
+
+//biggest request body we'll read, a long replay is still only a few kilobytes
+const MAX_BODY: usize = 1 << 20;
+const DEFAULT_LIMIT: usize = 10;
+//requests are answered one at a time so a client that goes quiet can't hold up everyone else
+const STREAM_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        port: 8080,
+        file: None,
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--port" => {
+                let port = value()?;
+                options.port = port
+                    .parse()
+                    .map_err(|_| format!("{} isn't a valid port", port))?;
+            }
+            "--file" => options.file = Some(value()?),
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    body: String,
+}
+
+fn read_request(stream: &TcpStream) -> io::Result<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let (path, query) = (path.to_string(), query.to_string());
+
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "body too big"));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Request {
+        method,
+        path,
+        query,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+fn respond(mut stream: &TcpStream, status: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+struct Leaderboard {
+    submissions: Vec<Submission>,
+    //of every replay on the board so the same run can't go up twice
+    hashes: HashSet<u64>,
+    file: Option<String>,
+}
+
+impl Leaderboard {
+    fn load(file: Option<String>) -> Result<Self, String> {
+        let submissions: Vec<Submission> = match &file {
+            Some(path) => match fs::read_to_string(path) {
+                Ok(text) => serde_json::from_str(&text)
+                    .map_err(|err| format!("couldn't read {}: {}", path, err))?,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+                Err(err) => return Err(format!("couldn't read {}: {}", path, err)),
+            },
+            None => Vec::new(),
+        };
+        let hashes = submissions
+            .iter()
+            .filter_map(|submission| submission.replay.parse::<Replay>().ok())
+            .map(|replay| replay.hash())
+            .collect();
+        Ok(Leaderboard {
+            submissions,
+            hashes,
+            file,
+        })
+    }
+
+    fn save(&self) {
+        if let Some(path) = &self.file {
+            let text = serde_json::to_string_pretty(&self.submissions)
+                .expect("submissions always serialize");
+            if let Err(err) = fs::write(path, text) {
+                eprintln!("couldn't save {}: {}", path, err);
+            }
+        }
+    }
+
+    fn top(&self, limit: usize) -> Vec<Entry> {
+        let mut entries: Vec<Entry> = self
+            .submissions
+            .iter()
+            .map(|submission| Entry {
+                name: submission.name.clone(),
+                score: submission.score,
+            })
+            .collect();
+        //stable sort so ties stay in the order they were sent
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        entries.truncate(limit);
+        entries
+    }
+
+    fn submit(&mut self, body: &str) -> Result<(), String> {
+        let submission: Submission =
+            serde_json::from_str(body).map_err(|err| format!("bad submission: {}", err))?;
+        if submission.name.trim().is_empty() {
+            return Err("name can't be empty".to_string());
+        }
+        let replay: Replay = submission
+            .replay
+            .parse()
+            .map_err(|err| format!("bad replay: {}", err))?;
+        if !leaderboard::ranked(&replay) {
+            return Err("only runs on the standard rules go on the leaderboard".to_string());
+        }
+        //the queue can send a run again if the answer got lost, it only counts once
+        let hash = replay.hash();
+        if self.hashes.contains(&hash) {
+            return Err("that run has already been sent".to_string());
+        }
+        //replayed on the server so nobody can just send a big number
+        verify(&replay, submission.score).map_err(|err| format!("replay rejected: {}", err))?;
+        println!("{} scored {}", submission.name, submission.score);
+        self.hashes.insert(hash);
+        self.submissions.push(submission);
+        self.save();
+        Ok(())
+    }
+
+    fn handle(&mut self, request: &Request) -> (&'static str, String) {
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/scores") => {
+                let limit = request
+                    .query
+                    .split('&')
+                    .find_map(|pair| pair.strip_prefix("limit="))
+                    .and_then(|limit| limit.parse().ok())
+                    .unwrap_or(DEFAULT_LIMIT);
+                let top =
+                    serde_json::to_string(&self.top(limit)).expect("entries always serialize");
+                ("200 OK", top)
+            }
+            ("POST", "/scores") => match self.submit(&request.body) {
+                Ok(()) => ("201 Created", "{}".to_string()),
+                Err(err) => ("422 Unprocessable Entity", error_body(&err)),
+            },
+            (_, "/scores") => ("405 Method Not Allowed", error_body("method not allowed")),
+            _ => ("404 Not Found", error_body("not found")),
+        }
+    }
+}
+
+fn error_body(err: &str) -> String {
+    serde_json::json!({ "error": err }).to_string()
+}
+
+fn main() {
+    let options = parse_options().unwrap_or_else(|err| {
+        eprintln!("{}\n{}", err, USAGE);
+        process::exit(2);
+    });
+    let mut leaderboard = Leaderboard::load(options.file).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+    let listener = TcpListener::bind(("127.0.0.1", options.port)).unwrap_or_else(|err| {
+        eprintln!("couldn't listen on port {}: {}", options.port, err);
+        process::exit(1);
+    });
+    println!("leaderboard listening on http://127.0.0.1:{}", options.port);
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        if let Err(err) = stream
+            .set_read_timeout(Some(STREAM_TIMEOUT))
+            .and_then(|()| stream.set_write_timeout(Some(STREAM_TIMEOUT)))
+        {
+            eprintln!("couldn't set a timeout on a connection: {}", err);
+            continue;
+        }
+        let result = match read_request(&stream) {
+            Ok(request) => {
+                let (status, body) = leaderboard.handle(&request);
+                respond(&stream, status, &body)
+            }
+            Err(err) => respond(&stream, "400 Bad Request", &error_body(&err.to_string())),
+        };
+        if let Err(err) = result {
+            eprintln!("couldn't answer a request: {}", err);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//Headless neuroevolution trainer
+//Every generation the whole population flies together in one simulation (same physics and pipes as the game),
+//the best birds are kept and the rest are replaced with mutated copies of the good ones.
+//The best genome is saved so the game can fly it with --genome <file>
+use flappybird::{
+    network::Network,
+    sim::{env::Observation, Event, Rng, Rules, World, TICK_RATE},
+};
+use std::{env, process, thread};
+
+struct Options {
+    generations: u32,
+    population: usize,
+    //courses every bird is tested on each generation, each one runs on its own thread
+    courses: u64,
+    //runs are cut off after this many seconds so a perfect bird doesn't fly forever
+    max_seconds: u64,
+    seed: u64,
+    out: String,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            generations: 100,
+            population: 200,
+            courses: 4,
+            max_seconds: 120,
+            seed: 0,
+            out: "best.genome".to_string(),
+        }
+    }
+}
+
+const USAGE: &str = "usage: train [--generations N] [--population N] [--courses N] [--max-seconds N] [--seed N] [--out FILE]";
+
+//birds kept as they are into the next generation
+const ELITES: usize = 10;
+const MUTATION_RATE: f32 = 0.2;
+const MUTATION_STRENGTH: f32 = 0.5;
+//a point is worth this many ticks of staying alive
+const SCORE_FITNESS: f32 = 1000.;
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--generations" => options.generations = parse(&value()?)?,
+            "--population" => options.population = parse(&value()?)?,
+            "--courses" => options.courses = parse(&value()?)?,
+            "--max-seconds" => options.max_seconds = parse(&value()?)?,
+            "--seed" => options.seed = parse(&value()?)?,
+            "--out" => options.out = value()?,
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+    if options.population <= ELITES {
+        return Err(format!("population has to be bigger than {}", ELITES));
+    }
+    if options.courses == 0 {
+        return Err("need at least one course".to_string());
+    }
+    Ok(options)
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} isn't a valid number", value))
+}
+
+//flies every network together on one course and returns how well each did
+fn fly_course(networks: &[Network], seed: u64, max_ticks: u64) -> Vec<f32> {
+    let mut world = World::new(Rules::default(), seed, networks.len());
+    let mut survived = vec![max_ticks; networks.len()];
+    while !world.all_dead() && world.tick() < max_ticks {
+        for (index, network) in networks.iter().enumerate() {
+            if world.birds()[index].alive() && network.should_flap(&Observation::new(&world, index))
+            {
+                world.flap(index);
+            }
+        }
+        let tick = world.tick();
+        for event in world.step() {
+            if let Event::Died { bird, .. } = event {
+                survived[*bird] = tick;
+            }
+        }
+    }
+    world
+        .birds()
+        .iter()
+        .zip(survived)
+        .map(|(bird, ticks)| bird.score as f32 * SCORE_FITNESS + ticks as f32)
+        .collect()
+}
+
+fn main() {
+    let options = parse_options().unwrap_or_else(|err| {
+        eprintln!("{}\n{}", err, USAGE);
+        process::exit(2);
+    });
+    let max_ticks = options.max_seconds * TICK_RATE as u64;
+    let mut rng = Rng::new(options.seed);
+    let mut population: Vec<Network> = (0..options.population)
+        .map(|_| Network::random(&mut rng))
+        .collect();
+    let mut best_fitness = f32::MIN;
+
+    for generation in 0..options.generations {
+        //new courses every generation so the birds can't just learn one
+        let seeds = (0..options.courses).map(|course| {
+            options
+                .seed
+                .wrapping_add(generation as u64 * options.courses + course)
+        });
+        let results: Vec<Vec<f32>> = thread::scope(|scope| {
+            let population = &population;
+            let handles: Vec<_> = seeds
+                .map(|seed| scope.spawn(move || fly_course(population, seed, max_ticks)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("course thread panicked"))
+                .collect()
+        });
+        let mut ranked: Vec<(f32, usize)> = (0..population.len())
+            .map(|index| {
+                let total: f32 = results.iter().map(|course| course[index]).sum();
+                (total / options.courses as f32, index)
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let (fitness, best) = ranked[0];
+        let mean = ranked.iter().map(|(fitness, _)| fitness).sum::<f32>() / ranked.len() as f32;
+        println!(
+            "generation {:>4}  best {:>9.1}  mean {:>9.1}  ~{:.1} points",
+            generation,
+            fitness,
+            mean,
+            fitness / SCORE_FITNESS
+        );
+        if fitness > best_fitness {
+            best_fitness = fitness;
+            if let Err(err) = population[best].save(&options.out) {
+                eprintln!("couldn't save {}: {}", options.out, err);
+                process::exit(1);
+            }
+        }
+
+        //keep the elites and fill the rest with mutated children of the top half
+        let parents = &ranked[..ranked.len() / 2];
+        let mut next: Vec<Network> = ranked[..ELITES]
+            .iter()
+            .map(|(_, index)| population[*index].clone())
+            .collect();
+        while next.len() < options.population {
+            let parent = parents[(rng.next_u64() % parents.len() as u64) as usize].1;
+            next.push(population[parent].mutate(&mut rng, MUTATION_RATE, MUTATION_STRENGTH));
+        }
+        population = next;
+    }
+    println!("best genome saved to {}", options.out);
+}
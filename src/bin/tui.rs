@@ -0,0 +1,329 @@
+//Terminal frontend, plays the game in a terminal with no gpu (over ssh or on a build box)
+//It drives the same headless simulation as the bevy game so the pipes, collisions and scoring
+//are identical, it just draws the world with half block characters (or plain ascii) instead of sprites.
+//Runs save a replay like the game does so they can be checked or evaluated afterwards
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Color, Print, SetBackgroundColor, SetForegroundColor},
+    terminal::{self, ClearType},
+};
+use flappybird::{
+    challenge::{Challenge, Course},
+    daily::{self, Date},
+    sim::{self, replay::Replay, Difficulty, Mutators, World, TICK_RATE},
+    storage,
+};
+use std::{
+    env,
+    io::{self, BufWriter, Write},
+    process,
+    time::{Duration, Instant},
+};
+
+struct Options {
+    //None picks a new random course every run
+    seed: Option<u64>,
+    daily: bool,
+    difficulty: Difficulty,
+    mutators: Mutators,
+    ascii: bool,
+}
+
+const USAGE: &str = "usage: tui [--seed N | --daily | --code CODE] [--difficulty easy|normal|hard] [--mutators a,b] [--ascii]";
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        seed: None,
+        daily: false,
+        difficulty: Difficulty::default(),
+        mutators: Mutators::NONE,
+        ascii: false,
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--seed" => {
+                let seed = value()?;
+                options.seed = Some(
+                    seed.parse()
+                        .map_err(|_| format!("{} isn't a valid number", seed))?,
+                );
+            }
+            "--daily" => options.daily = true,
+            //a code from the game sets up everything at once
+            "--code" => {
+                let challenge: Challenge = value()?.parse().map_err(|err| format!("{}", err))?;
+                match challenge.course {
+                    Course::Seed(seed) => options.seed = Some(seed),
+                    Course::Daily(_) => options.daily = true,
+                }
+                options.difficulty = challenge.difficulty;
+                options.mutators = challenge.mutators;
+            }
+            "--difficulty" => {
+                let name = value()?;
+                options.difficulty =
+                    Difficulty::from_name(&name).ok_or(format!("unknown difficulty {}", name))?;
+            }
+            "--mutators" => options.mutators = Mutators::from_names(&value()?)?,
+            "--ascii" => options.ascii = true,
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+impl Options {
+    //the run to play next, daily challenges are always on today's course
+    fn challenge(&self) -> Challenge {
+        if self.daily {
+            return Challenge {
+                course: Course::Daily(Date::today()),
+                difficulty: daily::DIFFICULTY,
+                mutators: Mutators::NONE,
+            };
+        }
+        Challenge {
+            course: Course::Seed(self.seed.unwrap_or_else(rand::random)),
+            difficulty: self.difficulty,
+            mutators: self.mutators,
+        }
+    }
+}
+
+//most frames drawn a second, redrawing every tick is a lot to push over ssh
+const FRAME_TIME: Duration = Duration::from_millis(33);
+
+//the part of the world the bevy game's camera shows
+const VIEW_HALF_WIDTH: f32 = 960.;
+
+const SKY: Color = Color::Reset;
+const PIPE: Color = Color::Green;
+const BIRD: Color = Color::Yellow;
+const DEAD_BIRD: Color = Color::DarkGrey;
+const TEXT: Color = Color::White;
+
+//puts the terminal back how it was however the game ends
+struct Terminal;
+
+impl Terminal {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Terminal)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+struct Run {
+    challenge: Challenge,
+    world: World,
+    replay: Replay,
+    flap: bool,
+    //printing would scribble over the screen in raw mode so it goes in the status line
+    save_error: Option<String>,
+}
+
+impl Run {
+    fn new(challenge: Challenge) -> Self {
+        Run {
+            world: World::new(challenge.rules(), challenge.seed(), 1),
+            replay: Replay::new(challenge.seed(), challenge.difficulty)
+                .with_mutators(challenge.mutators),
+            challenge,
+            flap: false,
+            save_error: None,
+        }
+    }
+
+    fn alive(&self) -> bool {
+        self.world.birds()[0].alive()
+    }
+
+    //same order as the game, the flap goes in and is recorded before the step
+    fn tick(&mut self) {
+        if self.flap && self.alive() {
+            self.world.flap(0);
+            self.replay.record(self.world.tick());
+        }
+        self.flap = false;
+        let died = self
+            .world
+            .step()
+            .iter()
+            .any(|event| matches!(event, sim::Event::Died { .. }));
+        if died {
+            if let Err(err) = self.replay.save(storage::last_replay_path()) {
+                self.save_error = Some(format!("couldn't save replay: {}", err));
+            }
+        }
+    }
+}
+
+//what's at a point in the world
+fn colour_at(world: &World, x: f32, y: f32) -> Color {
+    let rules = world.rules();
+    let bird = &world.birds()[0];
+    if (x - rules.bird_x).powi(2) + (y - bird.y).powi(2) <= rules.bird_radius.powi(2) {
+        return if bird.alive() { BIRD } else { DEAD_BIRD };
+    }
+    let pipe_length = rules.pipe_half_height * 2.;
+    for pipe in world.pipes() {
+        let from_gap = (y - pipe.gap_y).abs();
+        if (x - pipe.x).abs() <= rules.pipe_half_width
+            && from_gap >= rules.gap_half_height
+            && from_gap <= rules.gap_half_height + pipe_length
+        {
+            return PIPE;
+        }
+    }
+    SKY
+}
+
+//draws the world below a line of text, every cell is two pixels tall unless it's ascii
+fn render(out: &mut impl Write, run: &Run, ascii: bool) -> io::Result<()> {
+    let (columns, rows) = terminal::size()?;
+    let (columns, rows) = (columns as usize, rows.saturating_sub(1) as usize);
+    let rules = run.world.rules();
+    let pixel_rows = if ascii { rows } else { rows * 2 };
+    let pixel_width = VIEW_HALF_WIDTH * 2. / columns.max(1) as f32;
+    let pixel_height = (rules.ceiling - rules.floor) / pixel_rows.max(1) as f32;
+    let pixel = |column: usize, row: usize| {
+        colour_at(
+            &run.world,
+            -VIEW_HALF_WIDTH + (column as f32 + 0.5) * pixel_width,
+            rules.ceiling - (row as f32 + 0.5) * pixel_height,
+        )
+    };
+
+    queue!(out, cursor::MoveTo(0, 0), SetBackgroundColor(SKY))?;
+    let status: String = status(run).chars().take(columns).collect();
+    queue!(out, SetForegroundColor(TEXT), Print(status))?;
+    queue!(out, terminal::Clear(ClearType::UntilNewLine))?;
+    let mut colours = (TEXT, SKY);
+    for row in 0..rows {
+        queue!(out, cursor::MoveTo(0, row as u16 + 1))?;
+        for column in 0..columns {
+            let (character, foreground, background) = if ascii {
+                match pixel(column, row) {
+                    PIPE => ('#', PIPE, SKY),
+                    SKY => (' ', TEXT, SKY),
+                    bird => ('@', bird, SKY),
+                }
+            } else {
+                let (top, bottom) = (pixel(column, row * 2), pixel(column, row * 2 + 1));
+                if top == bottom {
+                    (' ', TEXT, top)
+                } else {
+                    ('▀', top, bottom)
+                }
+            };
+            //reset isn't a colour a foreground can be so the top half gets drawn as a bottom half instead
+            let (character, foreground, background) = if foreground == SKY && !ascii {
+                ('▄', background, SKY)
+            } else {
+                (character, foreground, background)
+            };
+            if colours.0 != foreground {
+                queue!(out, SetForegroundColor(foreground))?;
+            }
+            if colours.1 != background {
+                queue!(out, SetBackgroundColor(background))?;
+            }
+            colours = (foreground, background);
+            queue!(out, Print(character))?;
+        }
+    }
+    queue!(
+        out,
+        SetForegroundColor(Color::Reset),
+        SetBackgroundColor(Color::Reset)
+    )?;
+    out.flush()
+}
+
+fn status(run: &Run) -> String {
+    let score = run.world.birds()[0].score;
+    if run.alive() {
+        //fog and rain can't be drawn in a terminal so it just says what the weather's doing
+        let weather = match run.world.forecast() {
+            Some(forecast) => format!("  |  {}", forecast.weather().name()),
+            None => String::new(),
+        };
+        format!(" score {}{}  |  space to flap, q to quit", score, weather)
+    } else {
+        let error = match &run.save_error {
+            Some(err) => format!("  |  {}", err),
+            None => String::new(),
+        };
+        format!(
+            " game over, score {}  |  code {}  |  r to retry, q to quit{}",
+            score, run.challenge, error
+        )
+    }
+}
+
+fn main() {
+    let options = parse_options().unwrap_or_else(|err| {
+        eprintln!("{}\n{}", err, USAGE);
+        process::exit(2);
+    });
+    if let Err(err) = play(&options) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+fn play(options: &Options) -> io::Result<()> {
+    let _terminal = Terminal::enter()?;
+    let mut out = BufWriter::new(io::stdout());
+    let tick_length = Duration::from_secs(1) / TICK_RATE;
+    let mut run = Run::new(options.challenge());
+    let mut next_tick = Instant::now();
+    let mut last_frame = Instant::now() - FRAME_TIME;
+
+    loop {
+        //wait for input until the next tick is due
+        while event::poll(next_tick.saturating_duration_since(Instant::now()))? {
+            match event::read()? {
+                Event::Key(KeyEvent {
+                    code,
+                    modifiers,
+                    kind: KeyEventKind::Press,
+                    ..
+                }) => match code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                        return Ok(())
+                    }
+                    KeyCode::Char(' ' | 'w') | KeyCode::Up | KeyCode::Enter => run.flap = true,
+                    KeyCode::Char('r') if !run.alive() => {
+                        run = Run::new(options.challenge());
+                        next_tick = Instant::now();
+                    }
+                    _ => (),
+                },
+                Event::Resize(..) => queue!(out, terminal::Clear(ClearType::All))?,
+                _ => (),
+            }
+        }
+        //catches up if drawing fell behind so the game runs at the same speed as the bevy one
+        while Instant::now() >= next_tick {
+            run.tick();
+            next_tick += tick_length;
+        }
+        if last_frame.elapsed() >= FRAME_TIME {
+            render(&mut out, &run, options.ascii)?;
+            last_frame = Instant::now();
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//Checks a submitted replay really scores what it claims
+//Plays it back on the headless simulation and exits 0 if it checks out or 1 if it doesn't,
+//so a leaderboard (or a person) can run it on anything sent in
+use flappybird::sim::{replay::Replay, verify::verify, TICK_RATE};
+use serde::Serialize;
+use std::{env, process};
+
+struct Options {
+    replay: String,
+    score: u32,
+    json: bool,
+}
+
+const USAGE: &str = "usage: verify REPLAY --score N [--json]";
+
+fn parse_options() -> Result<Options, String> {
+    let mut replay = None;
+    let mut score = None;
+    let mut json = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--score" => {
+                let value = args.next().ok_or("--score needs a value")?;
+                score = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("{} isn't a valid number", value))?,
+                );
+            }
+            "--json" => json = true,
+            _ if arg.starts_with("--") => return Err(format!("unknown argument {}", arg)),
+            _ if replay.is_none() => replay = Some(arg),
+            _ => return Err(format!("only one replay at a time, got {} too", arg)),
+        }
+    }
+    Ok(Options {
+        replay: replay.ok_or("need a replay to check")?,
+        score: score.ok_or("need the --score the run claims")?,
+        json,
+    })
+}
+
+#[derive(Serialize)]
+struct Verdict {
+    replay: String,
+    claimed_score: u32,
+    valid: bool,
+    //why it was turned down, only there when it was
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    survival_seconds: Option<f64>,
+}
+
+fn main() {
+    let options = parse_options().unwrap_or_else(|err| {
+        eprintln!("{}\n{}", err, USAGE);
+        process::exit(2);
+    });
+    let verdict = match Replay::load(&options.replay) {
+        Err(err) => Verdict {
+            replay: options.replay.clone(),
+            claimed_score: options.score,
+            valid: false,
+            reason: Some(format!("couldn't load replay: {}", err)),
+            survival_seconds: None,
+        },
+        Ok(replay) => {
+            let result = verify(&replay, options.score);
+            Verdict {
+                replay: options.replay.clone(),
+                claimed_score: options.score,
+                valid: result.is_ok(),
+                reason: result.as_ref().err().map(|err| err.to_string()),
+                survival_seconds: result
+                    .ok()
+                    .map(|result| result.ticks as f64 / TICK_RATE as f64),
+            }
+        }
+    };
+
+    if options.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&verdict).expect("verdict always serializes")
+        );
+    } else if let Some(reason) = &verdict.reason {
+        println!("{} rejected: {}", verdict.replay, reason);
+    } else {
+        println!(
+            "{} ok: scores {} in {:.2}s",
+            verdict.replay,
+            verdict.claimed_score,
+            verdict.survival_seconds.unwrap_or_default()
+        );
+    }
+    if !verdict.valid {
+        process::exit(1);
+    }
+}
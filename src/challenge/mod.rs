@@ -0,0 +1,320 @@
+//Challenge codes, a short bit of text that sets up the exact same run somewhere else
+//it packs the course (a seed or a daily challenge day), difficulty and mutators into a few bytes
+//with a check byte so typos get caught, and writes them out in crockford base32 split up with dashes
+use crate::{
+    daily::Date,
+    sim::{Difficulty, Mutators, Rules},
+};
+use std::{fmt, str::FromStr};
+
+//bumped whenever the layout changes so old games can say so instead of playing the wrong thing
+pub const VERSION: u8 = 1;
+
+//no I, L, O or U so codes can't be misread, see https://www.crockford.com/base32.html
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+//characters between dashes
+const GROUP: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Course {
+    Seed(u64),
+    //that day's daily challenge
+    Daily(Date),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Challenge {
+    pub course: Course,
+    pub difficulty: Difficulty,
+    pub mutators: Mutators,
+}
+
+impl Challenge {
+    pub fn seed(&self) -> u64 {
+        match self.course {
+            Course::Seed(seed) => seed,
+            Course::Daily(date) => date.seed(),
+        }
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.mutators.rules(self.difficulty.rules())
+    }
+
+    //the bytes before they're turned into text, check byte last
+    fn to_bytes(self) -> Vec<u8> {
+        let difficulty = Difficulty::ALL
+            .iter()
+            .position(|difficulty| *difficulty == self.difficulty)
+            .expect("every difficulty is in ALL") as u8;
+        //course kind in the top bit, then difficulty, then mutators
+        let (kind, payload) = match self.course {
+            Course::Seed(seed) => (0, seed.to_le_bytes().to_vec()),
+            Course::Daily(date) => (1, (date.days() as i32).to_le_bytes().to_vec()),
+        };
+        let mut bytes = vec![VERSION, kind << 7 | difficulty << 5 | self.mutators.bits()];
+        bytes.extend(payload);
+        bytes.push(check_byte(&bytes));
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, CodeError> {
+        let (check, bytes) = bytes.split_last().ok_or(CodeError::Empty)?;
+        if bytes.len() < 2 || check_byte(bytes) != *check {
+            return Err(CodeError::Mistyped);
+        }
+        if bytes[0] != VERSION {
+            return Err(CodeError::Version(bytes[0]));
+        }
+        let packed = bytes[1];
+        let payload = &bytes[2..];
+        let difficulty = *Difficulty::ALL
+            .get((packed >> 5 & 0b11) as usize)
+            .ok_or(CodeError::Invalid("unknown difficulty"))?;
+        let mutators =
+            Mutators::from_bits(packed & 0b1_1111).ok_or(CodeError::Invalid("unknown mutators"))?;
+        let course = match packed >> 7 {
+            0 => Course::Seed(u64::from_le_bytes(
+                payload
+                    .try_into()
+                    .map_err(|_| CodeError::Invalid("wrong length for a seed"))?,
+            )),
+            _ => Course::Daily(Date::from_days(i32::from_le_bytes(
+                payload
+                    .try_into()
+                    .map_err(|_| CodeError::Invalid("wrong length for a daily"))?,
+            ) as i64)),
+        };
+        Ok(Challenge {
+            course,
+            difficulty,
+            mutators,
+        })
+    }
+
+    //a human readable rundown like "daily 2026-10-19, normal, low-gravity"
+    pub fn describe(&self) -> String {
+        let mut parts = vec![
+            match self.course {
+                Course::Seed(seed) => format!("seed {}", seed),
+                Course::Daily(date) => format!("daily {}", date),
+            },
+            self.difficulty.name().to_string(),
+        ];
+        parts.extend(self.mutators.names().into_iter().map(str::to_string));
+        parts.join(", ")
+    }
+}
+
+//written out as the code itself
+impl fmt::Display for Challenge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = encode(&self.to_bytes());
+        for (index, group) in text.as_bytes().chunks(GROUP).enumerate() {
+            if index > 0 {
+                write!(f, "-")?;
+            }
+            write!(f, "{}", String::from_utf8_lossy(group))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Challenge {
+    type Err = CodeError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        let bytes = decode(code)?;
+        if bytes.is_empty() {
+            return Err(CodeError::Empty);
+        }
+        Challenge::from_bytes(&bytes)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CodeError {
+    Empty,
+    BadCharacter(char),
+    //the check byte didn't match, most likely a typo or a bit missing
+    Mistyped,
+    //made by a game that writes a different version of codes
+    Version(u8),
+    Invalid(&'static str),
+}
+
+impl fmt::Display for CodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodeError::Empty => write!(f, "no code entered"),
+            CodeError::BadCharacter(c) => write!(f, "'{}' can't be in a code", c),
+            CodeError::Mistyped => write!(f, "that code doesn't check out, is it mistyped?"),
+            CodeError::Version(version) if *version > VERSION => write!(
+                f,
+                "that code is from a newer version of the game (code v{}, this game reads v{})",
+                version, VERSION
+            ),
+            CodeError::Version(version) => write!(
+                f,
+                "that code is from an older version of the game (code v{}, this game reads v{})",
+                version, VERSION
+            ),
+            CodeError::Invalid(reason) => write!(f, "that code isn't valid: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for CodeError {}
+
+//fnv-1a folded down to a byte
+fn check_byte(bytes: &[u8]) -> u8 {
+    let hash = bytes.iter().fold(0x811C_9DC5u32, |hash, byte| {
+        (hash ^ *byte as u32).wrapping_mul(0x0100_0193)
+    });
+    (hash ^ hash >> 8 ^ hash >> 16 ^ hash >> 24) as u8
+}
+
+fn encode(bytes: &[u8]) -> String {
+    let mut text = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in bytes {
+        buffer = buffer << 8 | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            text.push(ALPHABET[(buffer >> bits & 0b1_1111) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        text.push(ALPHABET[(buffer << (5 - bits) & 0b1_1111) as usize] as char);
+    }
+    text
+}
+
+//ignores dashes, spaces and case, and reads the easily confused letters as the digits they look like
+fn decode(text: &str) -> Result<Vec<u8>, CodeError> {
+    let mut bytes = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in text.chars() {
+        let value = match c.to_ascii_uppercase() {
+            '-' | ' ' | '\t' | '\n' | '\r' => continue,
+            'O' => 0,
+            'I' | 'L' => 1,
+            upper => ALPHABET
+                .iter()
+                .position(|letter| *letter as char == upper)
+                .ok_or(CodeError::BadCharacter(c))? as u32,
+        };
+        buffer = buffer << 5 | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //a code made straight from bytes with a good check byte, for layouts the game wouldn't write
+    fn code(mut bytes: Vec<u8>) -> String {
+        bytes.push(check_byte(&bytes));
+        encode(&bytes)
+    }
+
+    #[test]
+    fn roundtrip() {
+        let challenges = [
+            Challenge {
+                course: Course::Seed(0xDEAD_BEEF_1234_5678),
+                difficulty: Difficulty::Hard,
+                mutators: Mutators::LOW_GRAVITY.with(Mutators::WEATHER),
+            },
+            Challenge {
+                course: Course::Daily(Date::from_days(20_000)),
+                difficulty: Difficulty::Normal,
+                mutators: Mutators::NONE,
+            },
+        ];
+        for challenge in challenges {
+            let text = challenge.to_string();
+            assert_eq!(text.parse::<Challenge>(), Ok(challenge));
+            //dashes, case and look alike letters don't matter
+            let sloppy = text.replace('-', "").to_lowercase().replace('0', "o");
+            assert_eq!(sloppy.parse::<Challenge>(), Ok(challenge));
+        }
+    }
+
+    #[test]
+    fn bad_check_byte() {
+        let mut bytes = Challenge {
+            course: Course::Seed(42),
+            difficulty: Difficulty::Normal,
+            mutators: Mutators::NONE,
+        }
+        .to_bytes();
+        *bytes.last_mut().unwrap() ^= 1;
+        assert_eq!(
+            encode(&bytes).parse::<Challenge>(),
+            Err(CodeError::Mistyped)
+        );
+    }
+
+    #[test]
+    fn bad_character() {
+        assert_eq!(
+            "AB*C-1234".parse::<Challenge>(),
+            Err(CodeError::BadCharacter('*'))
+        );
+        assert_eq!(
+            "ABCU".parse::<Challenge>(),
+            Err(CodeError::BadCharacter('U'))
+        );
+    }
+
+    #[test]
+    fn wrong_length() {
+        assert_eq!("".parse::<Challenge>(), Err(CodeError::Empty));
+        assert_eq!(
+            code(vec![VERSION, 0, 1, 2, 3]).parse::<Challenge>(),
+            Err(CodeError::Invalid("wrong length for a seed"))
+        );
+        assert_eq!(
+            code(vec![VERSION, 1 << 7, 1, 2]).parse::<Challenge>(),
+            Err(CodeError::Invalid("wrong length for a daily"))
+        );
+    }
+
+    #[test]
+    fn unknown_version() {
+        let mut bytes = vec![VERSION + 1, 0];
+        bytes.extend(7u64.to_le_bytes());
+        let error = code(bytes).parse::<Challenge>().unwrap_err();
+        assert_eq!(error, CodeError::Version(VERSION + 1));
+        assert!(error.to_string().contains("newer version"));
+
+        let mut bytes = vec![0, 0];
+        bytes.extend(7u64.to_le_bytes());
+        let error = code(bytes).parse::<Challenge>().unwrap_err();
+        assert!(error.to_string().contains("older version"));
+    }
+
+    #[test]
+    fn unknown_mutators() {
+        let unused = (0..5)
+            .map(|bit| 1u8 << bit)
+            .find(|bit| Mutators::from_bits(*bit).is_none())
+            .expect("there's a spare mutator bit");
+        let mut bytes = vec![VERSION, unused];
+        bytes.extend(7u64.to_le_bytes());
+        assert_eq!(
+            code(bytes).parse::<Challenge>(),
+            Err(CodeError::Invalid("unknown mutators"))
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+
+//system clipboard so challenge codes can be copied out and pasted in
+//kept around for the whole run as on linux whatever was copied goes away with it
+pub struct ClipboardPlugin;
+
+impl Plugin for ClipboardPlugin {
+    fn build(&self, app: &mut App) {
+        let clipboard = arboard::Clipboard::new()
+            .map_err(|err| warn!("no clipboard, copy and paste won't work: {}", err))
+            .ok();
+        app.insert_non_send_resource(Clipboard(clipboard));
+    }
+}
+
+pub struct Clipboard(Option<arboard::Clipboard>);
+
+impl Clipboard {
+    pub fn get(&mut self) -> Option<String> {
+        let clipboard = self.0.as_mut()?;
+        clipboard
+            .get_text()
+            .map_err(|err| warn!("couldn't paste: {}", err))
+            .ok()
+    }
+
+    //returns whether it worked
+    pub fn set(&mut self, text: &str) -> bool {
+        let Some(clipboard) = self.0.as_mut() else {
+            return false;
+        };
+        clipboard
+            .set_text(text)
+            .map_err(|err| warn!("couldn't copy: {}", err))
+            .is_ok()
+    }
+}
@@ -0,0 +1,322 @@
+//Player options kept between runs in a json file in the data folder
+//every field has a default so an old or hand edited file with bits missing still loads
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{fs, io, path::Path};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    //folder of the theme in assets/themes, empty for the classic look
+    pub theme: String,
+    pub display: DisplayConfig,
+    pub audio: AudioConfig,
+    pub controls: ControlsConfig,
+    pub accessibility: AccessibilityConfig,
+    pub hitboxes: HitboxConfig,
+}
+
+//volumes from 0 to 1, music and effects are both scaled by master
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioConfig {
+    pub master: f32,
+    pub music: f32,
+    pub effects: f32,
+    pub sounds: SoundsConfig,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        AudioConfig {
+            master: 0.8,
+            music: 0.5,
+            effects: 1.,
+            sounds: SoundsConfig::default(),
+        }
+    }
+}
+
+impl AudioConfig {
+    pub fn music_volume(&self) -> f32 {
+        (self.master * self.music).clamp(0., 1.)
+    }
+
+    pub fn effects_volume(&self) -> f32 {
+        (self.master * self.effects).clamp(0., 1.)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    pub mode: WindowMode,
+    //only used in a window, fullscreen is whatever the monitor is
+    pub resolution: [u32; 2],
+    pub vsync: bool,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        DisplayConfig {
+            mode: WindowMode::Borderless,
+            resolution: [1920, 1080],
+            vsync: true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowMode {
+    Windowed,
+    //fullscreen without changing the monitor's resolution
+    Borderless,
+    Fullscreen,
+}
+
+impl WindowMode {
+    pub const ALL: [WindowMode; 3] = [
+        WindowMode::Windowed,
+        WindowMode::Borderless,
+        WindowMode::Fullscreen,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            WindowMode::Windowed => "windowed",
+            WindowMode::Borderless => "borderless",
+            WindowMode::Fullscreen => "fullscreen",
+        }
+    }
+}
+
+//what each action is bound to by name so the file is easy to edit by hand
+//keys go by the names bevy gives them (like "Space" or "KeyW"), mouse buttons like "MouseLeft"
+//and gamepad buttons like "PadSouth"
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ControlsConfig {
+    //one list for each player sharing the computer, gamepad buttons only count on that
+    //player's gamepad
+    #[serde(deserialize_with = "flap_bindings")]
+    pub flap: Vec<Vec<String>>,
+    pub pause: Vec<String>,
+    pub confirm: Vec<String>,
+    pub back: Vec<String>,
+    pub up: Vec<String>,
+    pub down: Vec<String>,
+    pub left: Vec<String>,
+    pub right: Vec<String>,
+    //copies the challenge code once a run is over
+    pub copy: Vec<String>,
+}
+
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+//files from before every action could be rebound have a single key for each player
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FlapBindings {
+    Lists(Vec<Vec<String>>),
+    Keys(Vec<String>),
+}
+
+//an old single key takes the place of the keyboard key in that player's defaults
+fn flap_bindings<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Vec<String>>, D::Error> {
+    Ok(match FlapBindings::deserialize(deserializer)? {
+        FlapBindings::Lists(lists) => lists,
+        FlapBindings::Keys(keys) => {
+            let defaults = ControlsConfig::default().flap;
+            keys.into_iter()
+                .enumerate()
+                .map(|(player, key)| {
+                    let mut names = defaults.get(player).cloned().unwrap_or_default();
+                    match names.first_mut() {
+                        Some(first) => *first = key,
+                        None => names.push(key),
+                    }
+                    names
+                })
+                .collect()
+        }
+    })
+}
+
+impl Default for ControlsConfig {
+    fn default() -> Self {
+        ControlsConfig {
+            flap: vec![
+                names(&["Space", "MouseLeft", "PadSouth"]),
+                names(&["KeyW", "PadSouth"]),
+                names(&["ArrowUp", "PadSouth"]),
+                names(&["ShiftRight", "PadSouth"]),
+            ],
+            pause: names(&["Escape", "KeyP", "PadStart"]),
+            confirm: names(&["Enter", "NumpadEnter", "PadSouth"]),
+            back: names(&["Escape", "PadEast"]),
+            up: names(&["ArrowUp", "PadDPadUp"]),
+            down: names(&["ArrowDown", "PadDPadDown"]),
+            left: names(&["ArrowLeft", "PadDPadLeft"]),
+            right: names(&["ArrowRight", "PadDPadRight"]),
+            copy: names(&["KeyC", "PadNorth"]),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilityConfig {
+    //turns off screen shake, zooming and freeze frames
+    pub reduced_motion: bool,
+    //player colours that stay apart with red green colour blindness
+    pub colourblind: bool,
+}
+
+//hitboxes traced from the bird and pipe sprites instead of the usual circle and boxes
+//only used on solo standard runs, they don't go on the leaderboard as replays can't play them back
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HitboxConfig {
+    pub traced: bool,
+    //pixels of the sprite shaved off all round so just clipping an edge isn't a death
+    pub forgiveness: f32,
+    //how opaque a pixel has to be to count, 0 to 255
+    pub threshold: u8,
+}
+
+impl Default for HitboxConfig {
+    fn default() -> Self {
+        HitboxConfig {
+            traced: false,
+            forgiveness: 4.,
+            threshold: 128,
+        }
+    }
+}
+
+//the sound effects are synthesized at startup from these, a file in assets/sounds replaces one
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundsConfig {
+    pub flap: Voice,
+    pub point: Voice,
+    pub hit: Voice,
+    pub fall: Voice,
+}
+
+impl Default for SoundsConfig {
+    fn default() -> Self {
+        SoundsConfig {
+            flap: Voice {
+                wave: Wave::Triangle,
+                pitch: 300.,
+                end_pitch: 650.,
+                attack: 0.005,
+                decay: 0.09,
+                noise: 0.3,
+                volume: 0.5,
+                ..Voice::default()
+            },
+            //two quick notes
+            point: Voice {
+                wave: Wave::Square,
+                pitch: 988.,
+                end_pitch: 1319.,
+                glide: false,
+                attack: 0.002,
+                decay: 0.3,
+                volume: 0.25,
+                ..Voice::default()
+            },
+            hit: Voice {
+                wave: Wave::Square,
+                pitch: 180.,
+                end_pitch: 50.,
+                attack: 0.001,
+                decay: 0.2,
+                noise: 0.7,
+                volume: 0.8,
+                ..Voice::default()
+            },
+            //mostly noise swept down so it sounds like air rushing past
+            fall: Voice {
+                wave: Wave::Sine,
+                pitch: 2500.,
+                end_pitch: 200.,
+                attack: 0.05,
+                decay: 0.45,
+                noise: 0.9,
+                volume: 0.6,
+                ..Voice::default()
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Wave {
+    #[default]
+    Sine,
+    Square,
+    Triangle,
+    Saw,
+}
+
+//one oscillator with a pitch that moves from pitch to end_pitch, mixed with noise
+//that's low passed at the same pitch, under an attack/decay envelope
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Voice {
+    pub wave: Wave,
+    //hz
+    pub pitch: f32,
+    pub end_pitch: f32,
+    //slides between the pitches, otherwise jumps halfway through
+    pub glide: bool,
+    //seconds
+    pub attack: f32,
+    pub decay: f32,
+    //0 is just the oscillator, 1 is just noise
+    pub noise: f32,
+    pub volume: f32,
+}
+
+impl Default for Voice {
+    fn default() -> Self {
+        Voice {
+            wave: Wave::Sine,
+            pitch: 440.,
+            end_pitch: 440.,
+            glide: true,
+            attack: 0.01,
+            decay: 0.2,
+            noise: 0.,
+            volume: 0.5,
+        }
+    }
+}
+
+impl Config {
+    //no file yet just means everything is default
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(
+            path,
+            serde_json::to_string_pretty(self).expect("config always serializes"),
+        )
+    }
+}
@@ -0,0 +1,224 @@
+//Daily challenge, one course a day that's the same for everyone
+//the seed only depends on the UTC date so anybody playing on the same day gets the same pipes,
+//and the best score for each day is kept so past days can be looked back on
+use crate::sim::Difficulty;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::Path,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+//everyone plays the daily on the same difficulty or it wouldn't be the same course
+pub const DIFFICULTY: Difficulty = Difficulty::Normal;
+
+//a day on the calendar, always UTC so timezones don't split the team up
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+impl Date {
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        Date::from_days((seconds / 86400) as i64)
+    }
+
+    //days since 1970-01-01, see https://howardhinnant.github.io/date_algorithms.html
+    pub fn from_days(days: i64) -> Self {
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days.rem_euclid(146097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        } as u32;
+        let year = (year_of_era + era * 400 + (month <= 2) as i64) as i32;
+        Date { year, month, day }
+    }
+
+    pub fn days(self) -> i64 {
+        let year = self.year as i64 - (self.month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let month = self.month as i64;
+        let day_of_year =
+            (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146097 + day_of_era - 719468
+    }
+
+    //the pipe seed for this day's challenge
+    pub fn seed(self) -> u64 {
+        let mut seed = (self.year as u64) << 16 | (self.month as u64) << 8 | self.day as u64;
+        //splitmix64 finalizer so days next to each other get nothing alike courses
+        seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        seed = (seed ^ (seed >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        seed = (seed ^ (seed >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        seed ^ (seed >> 31)
+    }
+
+    pub fn month_name(self) -> &'static str {
+        MONTHS[(self.month as usize - 1) % 12]
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parts = text.trim().splitn(3, '-');
+        let mut next = || parts.next().and_then(|part| part.parse().ok());
+        let (Some(year), Some(month), Some(day)) = (next(), next(), next()) else {
+            return Err(format!("{} isn't a YYYY-MM-DD date", text));
+        };
+        let date = Date {
+            year: year as i32,
+            month,
+            day,
+        };
+        //round tripping catches things like february 31st
+        if Date::from_days(date.days()) != date {
+            return Err(format!("{} isn't a real date", text));
+        }
+        Ok(date)
+    }
+}
+
+//best daily score for every day that's been played, kept as YYYY-MM-DD -> score
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DailyRecords {
+    best: BTreeMap<String, u32>,
+}
+
+impl DailyRecords {
+    //no file yet just means no days played
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(DailyRecords::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(
+            path,
+            serde_json::to_string_pretty(self).expect("records always serialize"),
+        )
+    }
+
+    pub fn best(&self, date: Date) -> Option<u32> {
+        self.best.get(&date.to_string()).copied()
+    }
+
+    //keeps the score if it beats that day's best, returns whether it did
+    pub fn record(&mut self, date: Date, score: u32) -> bool {
+        match self.best.get_mut(&date.to_string()) {
+            Some(best) if score <= *best => false,
+            Some(best) => {
+                *best = score;
+                true
+            }
+            None => {
+                self.best.insert(date.to_string(), score);
+                true
+            }
+        }
+    }
+
+    //the days up to and including the last one and how each went, newest first
+    pub fn calendar(&self, last: Date, days: usize) -> Vec<(Date, Option<u32>)> {
+        (0..days as i64)
+            .map(|back| Date::from_days(last.days() - back))
+            .map(|date| (date, self.best(date)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> Date {
+        Date { year, month, day }
+    }
+
+    #[test]
+    fn epoch_is_day_zero() {
+        assert_eq!(date(1970, 1, 1).days(), 0);
+        assert_eq!(Date::from_days(0), date(1970, 1, 1));
+        assert_eq!(Date::from_days(-1), date(1969, 12, 31));
+    }
+
+    #[test]
+    fn days_round_trip_around_leap_years() {
+        let known = [
+            (date(1900, 2, 28), -25509),
+            (date(1900, 3, 1), -25508),
+            (date(2000, 2, 29), 11016),
+            (date(2000, 3, 1), 11017),
+            (date(2023, 2, 28), 19416),
+            (date(2023, 3, 1), 19417),
+            (date(2024, 2, 29), 19782),
+            (date(2024, 3, 1), 19783),
+        ];
+        for (date, days) in known {
+            assert_eq!(date.days(), days, "{}", date);
+            assert_eq!(Date::from_days(days), date);
+        }
+        for days in -30000..30000 {
+            assert_eq!(Date::from_days(days).days(), days);
+        }
+    }
+
+    #[test]
+    fn only_real_dates_parse() {
+        assert_eq!("2024-02-29".parse(), Ok(date(2024, 2, 29)));
+        assert!("2023-02-29".parse::<Date>().is_err());
+        assert!("1900-02-29".parse::<Date>().is_err());
+    }
+
+    //changing this changes every daily course, old records would be for a different course
+    #[test]
+    fn seed_is_pinned() {
+        assert_eq!(date(2024, 2, 29).seed(), 0x6F5D_92ED_753C_8BB9);
+    }
+}
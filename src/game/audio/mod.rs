@@ -0,0 +1,200 @@
+use crate::game::{embedded, settings::Settings, SimulationEvent};
+use crate::{loading::Preload, AppState};
+use bevy::{
+    audio::{AddAudioSource, Volume},
+    prelude::*,
+};
+use flappybird::{
+    config::{SoundsConfig, Voice},
+    sim::{DeathCause, Event},
+};
+use synth::Synth;
+
+mod synth;
+
+//sound effects for what happens in the simulation and a music track for each screen
+pub struct AudioPlugin;
+
+impl Plugin for AudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_source::<Synth>()
+            .add_systems(Startup, load_music)
+            .add_systems(
+                Update,
+                (
+                    load_sounds,
+                    play_effects.run_if(in_state(AppState::Playing)),
+                )
+                    .chain(),
+            )
+            .add_systems(OnEnter(AppState::MainMenu), play_music)
+            .add_systems(OnEnter(AppState::Playing), play_music)
+            .add_systems(
+                Update,
+                set_music_volume.run_if(resource_changed::<Settings>),
+            );
+    }
+}
+
+//a sound file from the assets or one made up from the config
+#[derive(Clone)]
+pub enum Sound {
+    File(Handle<AudioSource>),
+    Synth(Handle<Synth>),
+}
+
+#[derive(Resource)]
+pub struct SoundEffects {
+    pub flap: Sound,
+    pub point: Sound,
+    pub hit: Sound,
+    //the swoosh of the bird dropping after it hits something
+    pub fall: Sound,
+}
+
+#[derive(Resource, Default)]
+struct Music {
+    menu: Option<Handle<AudioSource>>,
+    playing: Option<Handle<AudioSource>>,
+}
+
+//the music that's on right now
+#[derive(Component)]
+struct MusicTrack;
+
+//missing sounds are skipped instead of spamming load errors
+//the ones that are there get waited on by the loading screen
+fn load_optional(
+    asset_server: &AssetServer,
+    preload: &mut Preload,
+    path: &str,
+) -> Option<Handle<AudioSource>> {
+    let handle = embedded::exists(path).then(|| asset_server.load(path.to_string()))?;
+    preload.add(path, &handle);
+    Some(handle)
+}
+
+fn load_sound(
+    asset_server: &AssetServer,
+    preload: &mut Preload,
+    synths: &mut Assets<Synth>,
+    path: &str,
+    voice: &Voice,
+) -> Sound {
+    match load_optional(asset_server, preload, path) {
+        Some(file) => Sound::File(file),
+        None => Sound::Synth(synths.add(Synth::new(voice))),
+    }
+}
+
+//at startup and again whenever the sounds in the config are changed
+fn load_sounds(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut synths: ResMut<Assets<Synth>>,
+    mut preload: ResMut<Preload>,
+    settings: Res<Settings>,
+    mut loaded: Local<Option<SoundsConfig>>,
+) {
+    let sounds = &settings.audio.sounds;
+    if loaded.as_ref() == Some(sounds) {
+        return;
+    }
+    *loaded = Some(sounds.clone());
+    let mut load = |path, voice| load_sound(&asset_server, &mut preload, &mut synths, path, voice);
+    commands.insert_resource(SoundEffects {
+        flap: load("sounds/flap.ogg", &sounds.flap),
+        point: load("sounds/point.ogg", &sounds.point),
+        hit: load("sounds/hit.ogg", &sounds.hit),
+        fall: load("sounds/fall.ogg", &sounds.fall),
+    });
+}
+
+fn load_music(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut preload: ResMut<Preload>,
+) {
+    commands.insert_resource(Music {
+        menu: load_optional(&asset_server, &mut preload, "music/menu.ogg"),
+        playing: load_optional(&asset_server, &mut preload, "music/playing.ogg"),
+    });
+}
+
+fn play(commands: &mut Commands, sound: &Sound, volume: f32) {
+    let settings = PlaybackSettings::DESPAWN.with_volume(Volume::new(volume));
+    match sound {
+        Sound::File(source) => commands.spawn(AudioBundle {
+            source: source.clone(),
+            settings,
+        }),
+        Sound::Synth(source) => commands.spawn(AudioSourceBundle {
+            source: source.clone(),
+            settings,
+        }),
+    };
+}
+
+//flaps, points and deaths straight from the simulation so every bird gets its sounds
+fn play_effects(
+    mut commands: Commands,
+    mut events: EventReader<SimulationEvent>,
+    effects: Option<Res<SoundEffects>>,
+    settings: Res<Settings>,
+) {
+    let Some(effects) = effects else {
+        return;
+    };
+    let volume = settings.audio.effects_volume();
+    for event in events.read() {
+        match **event {
+            Event::Flapped { .. } => play(&mut commands, &effects.flap, volume),
+            Event::Scored { .. } => play(&mut commands, &effects.point, volume),
+            Event::Died { cause, .. } => {
+                play(&mut commands, &effects.hit, volume);
+                //hitting the ground is the end of the fall already
+                if cause != DeathCause::Ground {
+                    play(&mut commands, &effects.fall, volume);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+//swaps to the track for the screen we're on
+fn play_music(
+    mut commands: Commands,
+    tracks: Query<Entity, With<MusicTrack>>,
+    music: Option<Res<Music>>,
+    state: Res<State<AppState>>,
+    settings: Res<Settings>,
+) {
+    for track in tracks.iter() {
+        commands.entity(track).despawn();
+    }
+    let Some(music) = music else {
+        return;
+    };
+    let track = match state.get() {
+        AppState::Loading => return,
+        AppState::MainMenu => &music.menu,
+        AppState::Playing => &music.playing,
+    };
+    if let Some(track) = track {
+        commands.spawn((
+            AudioBundle {
+                source: track.clone(),
+                settings: PlaybackSettings::LOOP
+                    .with_volume(Volume::new(settings.audio.music_volume())),
+            },
+            MusicTrack,
+        ));
+    }
+}
+
+fn set_music_volume(sinks: Query<&AudioSink, With<MusicTrack>>, settings: Res<Settings>) {
+    for sink in sinks.iter() {
+        sink.set_volume(settings.audio.music_volume());
+    }
+}
@@ -0,0 +1,122 @@
+use bevy::{
+    audio::{Decodable, Source},
+    prelude::*,
+};
+use flappybird::config::{Voice, Wave};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{f32::consts::TAU, sync::Arc, time::Duration};
+
+const SAMPLE_RATE: u32 = 44100;
+
+//a sound made from a voice in the config, played like any other bevy audio source
+#[derive(Asset, TypePath)]
+pub struct Synth {
+    samples: Arc<[f32]>,
+}
+
+impl Synth {
+    pub fn new(voice: &Voice) -> Self {
+        Synth {
+            samples: render(voice).into(),
+        }
+    }
+}
+
+impl Decodable for Synth {
+    type DecoderItem = f32;
+    type Decoder = SynthDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        SynthDecoder {
+            samples: self.samples.clone(),
+            position: 0,
+        }
+    }
+}
+
+pub struct SynthDecoder {
+    samples: Arc<[f32]>,
+    position: usize,
+}
+
+impl Iterator for SynthDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.samples.get(self.position).copied();
+        self.position += 1;
+        sample
+    }
+}
+
+impl Source for SynthDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some(self.samples.len().saturating_sub(self.position))
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(
+            self.samples.len() as f32 / SAMPLE_RATE as f32,
+        ))
+    }
+}
+
+fn render(voice: &Voice) -> Vec<f32> {
+    let attack = voice.attack.max(0.);
+    let decay = voice.decay.max(0.);
+    let length = attack + decay;
+    let count = (length * SAMPLE_RATE as f32) as usize;
+    //fixed seed so a sound is the same every time the game starts
+    let mut rng = StdRng::seed_from_u64(0);
+    let noise = voice.noise.clamp(0., 1.);
+    let (mut phase, mut filtered) = (0f32, 0f32);
+
+    (0..count)
+        .map(|i| {
+            let time = i as f32 / SAMPLE_RATE as f32;
+            let progress = time / length;
+            let pitch = if voice.glide {
+                voice.pitch * (voice.end_pitch / voice.pitch).powf(progress)
+            } else if progress < 0.5 {
+                voice.pitch
+            } else {
+                voice.end_pitch
+            }
+            .max(1.);
+
+            phase = (phase + pitch / SAMPLE_RATE as f32).fract();
+            let tone = match voice.wave {
+                Wave::Sine => (phase * TAU).sin(),
+                Wave::Square => {
+                    if phase < 0.5 {
+                        1.
+                    } else {
+                        -1.
+                    }
+                }
+                Wave::Triangle => 4. * (phase - 0.5).abs() - 1.,
+                Wave::Saw => 2. * phase - 1.,
+            };
+            //one pole low pass, so the noise follows the pitch too
+            let smoothing = 1. - (-TAU * pitch / SAMPLE_RATE as f32).exp();
+            filtered += smoothing * (rng.gen_range(-1f32..1.) - filtered);
+
+            let envelope = if time < attack {
+                time / attack
+            } else {
+                (1. - (time - attack) / decay.max(f32::EPSILON))
+                    .max(0.)
+                    .powi(2)
+            };
+            (tone * (1. - noise) + filtered * noise) * envelope * voice.volume.clamp(0., 1.)
+        })
+        .collect()
+}
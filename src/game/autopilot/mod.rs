@@ -0,0 +1,60 @@
+use crate::game::bird::{BirdInput, Controller, FlapInput, SimBird};
+use crate::game::{Simulation, StepSimulation};
+use crate::AppState;
+use bevy::prelude::*;
+use flappybird::{
+    network::Network,
+    sim::{bot, env::Observation},
+};
+
+//a bot that flies the bird on its own, used for soak testing and attract mode
+pub struct AutopilotPlugin;
+
+impl Plugin for AutopilotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Autopilot>()
+            .add_systems(
+                FixedUpdate,
+                autopilot_input
+                    .in_set(BirdInput)
+                    .before(StepSimulation)
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(
+                OnEnter(AppState::MainMenu),
+                restart_run.run_if(|autopilot: Res<Autopilot>| autopilot.soak),
+            );
+    }
+}
+
+//enabled makes the next bird spawn with the autopilot
+//soak skips the main menu so the bot keeps playing run after run
+//network is a trained genome to fly with instead of the built in bot
+#[derive(Resource, Default)]
+pub struct Autopilot {
+    pub enabled: bool,
+    pub soak: bool,
+    pub network: Option<Network>,
+}
+
+//flaps autopilot controlled birds, runs on the fixed tick so it sees every step like it would headless
+fn autopilot_input(
+    mut bird: Query<(&SimBird, &Controller, &mut FlapInput)>,
+    simulation: Res<Simulation>,
+    autopilot: Res<Autopilot>,
+) {
+    for (sim_bird, controller, mut flap) in bird.iter_mut() {
+        if let Controller::Autopilot = controller {
+            let observation = Observation::new(&simulation, sim_bird.0);
+            flap.0 = match &autopilot.network {
+                Some(network) => network.should_flap(&observation),
+                None => bot::should_flap(&observation),
+            };
+        }
+    }
+}
+
+//starts another run straight away instead of waiting on the menu
+fn restart_run(mut state: ResMut<NextState<AppState>>) {
+    state.set(AppState::Playing);
+}
@@ -1,19 +1,42 @@
 use crate as main;
-use crate::{game::Game, AppState};
+use crate::{
+    game::{
+        autopilot::Autopilot,
+        input::{Action, Actions},
+        pause::Paused,
+        settings::Settings,
+        start_simulation,
+        theme::{self, Theme},
+        versus::Versus,
+        Game, Players, Recording, Simulation, StepSimulation,
+    },
+    loading::GameAssets,
+    AppState,
+};
 use bevy::prelude::*;
-use bevy_rapier2d::prelude::*;
 
 pub struct BirdPlugin;
 
 impl Plugin for BirdPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Playing), setup)
+        app.add_systems(OnEnter(AppState::Playing), setup.after(start_simulation))
             .add_systems(
                 Update,
                 (
-                    jump.run_if(in_state(main::game::GameState::Playing)),
-                    rotate_based_on_velocity,
+                    player_input
+                        .in_set(BirdInput)
+                        .run_if(|paused: Res<Paused>| !paused.on && !paused.resumed),
+                    (follow_simulation, rotate_based_on_velocity, grey_out_dead)
+                        .chain()
+                        .run_if(in_state(AppState::Playing)),
                 ),
+            )
+            .add_systems(
+                FixedUpdate,
+                jump.after(BirdInput)
+                    .before(StepSimulation)
+                    .run_if(not(resource_exists::<Versus>))
+                    .run_if(in_state(main::game::GameState::Playing)),
             );
     }
 }
@@ -21,77 +44,204 @@ impl Plugin for BirdPlugin {
 #[derive(Component)]
 pub struct Bird;
 
+//what is flying the bird, a player here, the autopilot or someone over the network
+#[derive(Component, Clone, Copy)]
+pub enum Controller {
+    //flaps with that player's flap bindings
+    Player(usize),
+    Autopilot,
+    Remote,
+}
+
+//which bird in the simulation this entity is showing
+#[derive(Component, Clone, Copy)]
+pub struct SimBird(pub usize);
+
+//whether the bird wants to flap, controllers set it and jump uses it up on the next tick
+#[derive(Component, Default)]
+pub struct FlapInput(pub bool);
+
+//every system that writes FlapInput goes in here so jump always sees the latest input
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct BirdInput;
+
 #[derive(Bundle)]
 struct FlappyBird {
     pub name: Name,
-    pub rigidbody: RigidBody,
-    pub collider: Collider,
-    pub gravityscale: GravityScale,
-    pub velocity: Velocity,
     pub bird: Bird,
+    pub sim_bird: SimBird,
+    pub controller: Controller,
+    pub flap: FlapInput,
     pub game: Game,
 }
 
-// The first Comment
-//CIRCLERADIUS is a tuple so that we can have more forgiving hitboxes
-//The first number is the width of the sprite/mesh and the second number is the offset so its more forgiving
-const CIRCLERADIUS: (f32, f32) = (30., 5.);
+//colours for each player sharing the computer, player 1 keeps the plain sprite
+pub const PLAYER_COLOURS: [Color; 4] = [
+    Color::WHITE,
+    Color::rgb(1., 0.6, 0.6),
+    Color::rgb(0.6, 0.8, 1.),
+    Color::rgb(0.7, 1., 0.6),
+];
+//orange, blue and yellow stay apart with red green colour blindness
+const COLOURBLIND_PLAYER_COLOURS: [Color; 4] = [
+    Color::WHITE,
+    Color::rgb(1., 0.65, 0.1),
+    Color::rgb(0.35, 0.7, 1.),
+    Color::rgb(1., 0.95, 0.3),
+];
+//tint for birds that are out while the others keep going
+const DEAD_COLOUR: Color = Color::rgba(0.4, 0.4, 0.4, 0.6);
+//the other player in a versus match is see through
+const GHOST_ALPHA: f32 = 0.4;
 
-const JUMPHEIGHT: f32 = 425.;
+pub fn player_colour(player: usize, settings: &Settings) -> Color {
+    if settings.accessibility.colourblind {
+        COLOURBLIND_PLAYER_COLOURS[player]
+    } else {
+        PLAYER_COLOURS[player]
+    }
+}
 
 impl Default for FlappyBird {
     fn default() -> Self {
-        let gravity: f32 = 10.;
         FlappyBird {
             name: Name::from("Bird"),
-            rigidbody: RigidBody::Dynamic,
-            collider: Collider::ball(CIRCLERADIUS.0 - CIRCLERADIUS.1),
-            gravityscale: GravityScale(gravity),
-            velocity: Velocity { ..default() },
             bird: Bird,
+            sim_bird: SimBird(0),
+            controller: Controller::Player(0),
+            flap: FlapInput::default(),
             game: Game,
         }
     }
 }
 
-//spawns the bird with a custom sprite
-fn setup(mut commands: Commands, assets_server: Res<AssetServer>) {
-    commands
-        .spawn((
-            FlappyBird { ..default() },
-            SpriteBundle {
-                texture: assets_server.load("Sprites/BirdBody.png"),
-                sprite: Sprite {
-                    custom_size: Some(Vec2::new(60., 70.)),
-                    anchor: bevy::sprite::Anchor::Custom(Vec2::new(-0.02, -0.08)),
+//spawns a bird with a custom sprite for every player
+#[allow(clippy::too_many_arguments)]
+fn setup(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    autopilot: Res<Autopilot>,
+    players: Res<Players>,
+    versus: Option<Res<Versus>>,
+    theme: Res<Theme>,
+    settings: Res<Settings>,
+    simulation: Res<Simulation>,
+) {
+    //this run's rules rather than the defaults so the sprite always matches the collider
+    let rules = simulation.rules();
+    let look = &theme.bird;
+    let tint = theme::colour(look.colour);
+    for player in 0..players.0 {
+        let player_colour = player_colour(player, &settings);
+        let mut colour = Color::rgba(
+            player_colour.r() * tint.r(),
+            player_colour.g() * tint.g(),
+            player_colour.b() * tint.b(),
+            tint.a(),
+        );
+        //in versus the local player always uses player 1's bindings
+        let controller = match &versus {
+            Some(versus) if versus.remote_bird() == player => {
+                colour.set_a(GHOST_ALPHA);
+                Controller::Remote
+            }
+            Some(_) => Controller::Player(0),
+            None if autopilot.enabled => Controller::Autopilot,
+            None => Controller::Player(player),
+        };
+        commands
+            .spawn((
+                FlappyBird {
+                    name: Name::from(format!("Bird {}", player + 1)),
+                    sim_bird: SimBird(player),
+                    controller,
+                    ..default()
+                },
+                SpriteBundle {
+                    texture: assets.bird.clone(),
+                    sprite: Sprite {
+                        color: colour,
+                        custom_size: Some(look.size(rules.bird_radius)),
+                        anchor: theme::anchor(look.anchor),
+                        ..default()
+                    },
                     ..default()
                 },
-                ..default()
-            },
-        ))
-        .insert(TransformBundle::from(Transform::from_xyz(-400., 0., 0.)));
+            ))
+            //later players sit slightly behind so everyone can still be seen when they overlap
+            .insert(TransformBundle::from(Transform::from_xyz(
+                rules.bird_x,
+                0.,
+                -(player as f32) * 0.1,
+            )));
+    }
 }
 
-//jumps when you press space
-fn jump(mut bird: Query<&mut Velocity, With<Bird>>, input: Res<ButtonInput<KeyCode>>) {
-    for mut velocity in bird.iter_mut() {
-        if input.just_pressed(KeyCode::Space) {
-            velocity.linvel.y = JUMPHEIGHT;
+//flaps player controlled birds when their flap is pressed
+//paused presses are dropped so the bird doesn't jump the moment it carries on
+fn player_input(mut bird: Query<(&Controller, &mut FlapInput), With<Bird>>, actions: Res<Actions>) {
+    for (controller, mut flap) in bird.iter_mut() {
+        //only ever set here so a press isn't lost on a frame without a tick
+        if let Controller::Player(player) = controller {
+            if actions.just_pressed(Action::Flap(*player)) {
+                flap.0 = true;
+            }
         }
     }
 }
 
-//rotates the bird based on vertical velocity
-fn rotate_based_on_velocity(mut bird: Query<(&mut Transform, &Velocity), With<Bird>>) {
-    for (mut transform, velocity) in bird.iter_mut() {
-        let mut angle = velocity.linvel.y / 10.;
-        if angle > 45. {
-            angle = 45.
+//jumps when the bird's controller asked for a flap
+fn jump(
+    mut bird: Query<(&SimBird, &mut FlapInput)>,
+    mut simulation: ResMut<Simulation>,
+    mut recording: ResMut<Recording>,
+) {
+    for (sim_bird, mut flap) in bird.iter_mut() {
+        if flap.0 {
+            simulation.flap(sim_bird.0);
+            //only flaps that actually happened go in the replay or it won't verify
+            if sim_bird.0 == 0 && simulation.birds()[0].alive() {
+                recording.record(simulation.tick());
+            }
         }
-        if angle < -90. {
-            angle = -90.;
+        flap.0 = false;
+    }
+}
+
+//moves the sprite to wherever the simulation has the bird
+fn follow_simulation(mut bird: Query<(&mut Transform, &SimBird)>, simulation: Res<Simulation>) {
+    for (mut transform, sim_bird) in bird.iter_mut() {
+        if let Some(bird) = simulation.birds().get(sim_bird.0) {
+            transform.translation.y = bird.y;
         }
+    }
+}
 
-        transform.rotation = Quat::from_rotation_z(main::game::degrees_to_radians(angle));
+//rotates the bird based on vertical velocity
+fn rotate_based_on_velocity(
+    mut bird: Query<(&mut Transform, &SimBird)>,
+    simulation: Res<Simulation>,
+) {
+    for (mut transform, sim_bird) in bird.iter_mut() {
+        let Some(bird) = simulation.birds().get(sim_bird.0) else {
+            continue;
+        };
+        transform.rotation = Quat::from_rotation_z(main::game::degrees_to_radians(bird.tilt()));
+    }
+}
+
+//greys out birds that have died in multiplayer so the ones still flying stand out
+fn grey_out_dead(mut bird: Query<(&mut Sprite, &SimBird)>, simulation: Res<Simulation>) {
+    if simulation.birds().len() < 2 {
+        return;
+    }
+    for (mut sprite, sim_bird) in bird.iter_mut() {
+        if simulation
+            .birds()
+            .get(sim_bird.0)
+            .is_some_and(|bird| !bird.alive())
+        {
+            sprite.color = DEAD_COLOUR;
+        }
     }
 }
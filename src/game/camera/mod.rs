@@ -0,0 +1,133 @@
+use crate::game::{settings::Settings, versus::Versus, SimulationEvent};
+use crate::AppState;
+use bevy::prelude::*;
+use flappybird::sim::Event;
+
+//screen shake when a bird dies, a freeze frame on the hit and a little zoom on every point
+//all of it is off with reduced motion in the settings
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraEffects>()
+            .add_systems(OnExit(AppState::Playing), reset)
+            .add_systems(
+                Update,
+                (react, hit_stop, move_camera)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+//furthest the camera gets thrown at full trauma
+const MAX_OFFSET: f32 = 30.;
+//radians
+const MAX_ROLL: f32 = 0.04;
+//trauma lost every second, it's squared for the shake so it tails off smoothly
+const TRAUMA_DECAY: f32 = 1.4;
+const DEATH_TRAUMA: f32 = 0.7;
+//how fast the shake wobbles
+const SHAKE_SPEED: f32 = 25.;
+//real seconds the game stops for when a bird hits something
+const HIT_STOP: f32 = 0.09;
+//how far in a point zooms and how quickly it settles back
+const PUNCH: f32 = 0.03;
+const PUNCH_DECAY: f32 = 6.;
+
+#[derive(Resource, Default)]
+struct CameraEffects {
+    //0 to 1
+    trauma: f32,
+    //0 to 1, how much of the zoom punch is left
+    punch: f32,
+    //real seconds left of the freeze frame
+    frozen: f32,
+    //whether the freeze frame is what's holding the clock, so it only ever lets go of its own stop
+    stopped: bool,
+}
+
+fn react(
+    mut events: EventReader<SimulationEvent>,
+    mut effects: ResMut<CameraEffects>,
+    settings: Res<Settings>,
+    versus: Option<Res<Versus>>,
+) {
+    for event in events.read() {
+        if settings.accessibility.reduced_motion {
+            continue;
+        }
+        match **event {
+            Event::Died { .. } => {
+                effects.trauma = (effects.trauma + DEATH_TRAUMA).min(1.);
+                //stopping the clock would hold up the other player in versus
+                if versus.is_none() {
+                    effects.frozen = HIT_STOP;
+                }
+            }
+            Event::Scored { .. } => effects.punch = 1.,
+            _ => (),
+        }
+    }
+}
+
+//stops virtual time for the freeze frame, the simulation and everything drawn off it stop with it
+//it slows the clock to nothing instead of pausing it so it can't undo the pause menu's pause
+//pausing holds the freeze frame where it is
+fn hit_stop(
+    mut effects: ResMut<CameraEffects>,
+    mut time: ResMut<Time<Virtual>>,
+    real: Res<Time<Real>>,
+) {
+    if effects.frozen > 0. {
+        if !effects.stopped {
+            time.set_relative_speed(0.);
+            effects.stopped = true;
+        }
+        if !time.is_paused() {
+            effects.frozen -= real.delta_seconds();
+        }
+    } else if effects.stopped {
+        time.set_relative_speed(1.);
+        effects.stopped = false;
+    }
+}
+
+//uses real time so the camera still shakes through the freeze frame
+fn move_camera(
+    mut effects: ResMut<CameraEffects>,
+    mut camera: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+    real: Res<Time<Real>>,
+) {
+    let delta = real.delta_seconds();
+    effects.trauma = (effects.trauma - TRAUMA_DECAY * delta).max(0.);
+    effects.punch = (effects.punch - PUNCH_DECAY * delta).max(0.);
+
+    let shake = effects.trauma * effects.trauma;
+    let t = real.elapsed_seconds() * SHAKE_SPEED;
+    //a few sines that don't line up make a smooth wobble without needing a noise function
+    let wobble = |offset: f32| (t + offset).sin() * 0.6 + (t * 2.3 + offset * 1.7).sin() * 0.4;
+    for (mut transform, mut projection) in camera.iter_mut() {
+        transform.translation.x = wobble(0.) * MAX_OFFSET * shake;
+        transform.translation.y = wobble(10.) * MAX_OFFSET * shake;
+        transform.rotation = Quat::from_rotation_z(wobble(20.) * MAX_ROLL * shake);
+        projection.scale = 1. - PUNCH * effects.punch * effects.punch;
+    }
+}
+
+fn reset(
+    mut effects: ResMut<CameraEffects>,
+    mut camera: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if effects.stopped {
+        time.set_relative_speed(1.);
+    }
+    *effects = CameraEffects::default();
+    for (mut transform, mut projection) in camera.iter_mut() {
+        transform.translation.x = 0.;
+        transform.translation.y = 0.;
+        transform.rotation = Quat::IDENTITY;
+        projection.scale = 1.;
+    }
+}
@@ -0,0 +1,104 @@
+use crate::clipboard::Clipboard;
+use crate::game::{
+    input::{Action, Actions, Bindings},
+    Game, GameState,
+};
+use crate::AppState;
+use bevy::prelude::*;
+use flappybird::{
+    challenge::{Challenge, Course},
+    sim::{Difficulty, Mutators},
+};
+
+//shows the code for the run that was just played so it can be sent to someone else
+pub struct ChallengePlugin;
+
+impl Plugin for ChallengePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CurrentChallenge>()
+            .add_systems(
+                OnEnter(GameState::Dead),
+                show_code.run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(
+                Update,
+                copy_code
+                    .run_if(in_state(GameState::Dead))
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+//what the current run is playing, set when it starts
+#[derive(Resource)]
+pub struct CurrentChallenge(pub Challenge);
+
+impl Default for CurrentChallenge {
+    fn default() -> Self {
+        CurrentChallenge(Challenge {
+            course: Course::Seed(0),
+            difficulty: Difficulty::default(),
+            mutators: Mutators::NONE,
+        })
+    }
+}
+
+#[derive(Component)]
+struct CodeText;
+
+fn code_label(challenge: &Challenge, copied: Option<bool>, bindings: &Bindings) -> String {
+    let hint = match copied {
+        None => format!("{} to copy", bindings.name(Action::Copy)),
+        Some(true) => "copied!".to_string(),
+        Some(false) => "couldn't copy".to_string(),
+    };
+    format!("Challenge code  {}  ({})", challenge, hint)
+}
+
+//puts the code along the bottom of the screen
+fn show_code(mut commands: Commands, current: Res<CurrentChallenge>, bindings: Res<Bindings>) {
+    info!("challenge code {} ({})", current.0, current.0.describe());
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(50.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            Game,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    code_label(&current.0, None, &bindings),
+                    TextStyle {
+                        font_size: 40.,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                CodeText,
+            ));
+        });
+}
+
+fn copy_code(
+    actions: Res<Actions>,
+    bindings: Res<Bindings>,
+    current: Res<CurrentChallenge>,
+    mut clipboard: NonSendMut<Clipboard>,
+    mut text: Query<&mut Text, With<CodeText>>,
+) {
+    if !actions.just_pressed(Action::Copy) {
+        return;
+    }
+    let copied = clipboard.set(&current.0.to_string());
+    for mut text in text.iter_mut() {
+        text.sections[0].value = code_label(&current.0, Some(copied), &bindings);
+    }
+}
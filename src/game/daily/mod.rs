@@ -0,0 +1,90 @@
+use crate::game::{autopilot::Autopilot, Game, GameState, Mode, Players, Score};
+use crate::AppState;
+use bevy::prelude::*;
+use flappybird::{daily::DailyRecords, storage};
+
+//keeps the best score of every daily challenge and shows which day is being played
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Daily(
+            DailyRecords::load(storage::daily_records_path()).unwrap_or_else(|err| {
+                warn!("couldn't load daily records: {}", err);
+                DailyRecords::default()
+            }),
+        ))
+        .add_systems(
+            OnEnter(AppState::Playing),
+            setup.run_if(|mode: Res<Mode>| matches!(*mode, Mode::Daily(_))),
+        )
+        .add_systems(
+            OnEnter(GameState::Dead),
+            record_result.run_if(in_state(AppState::Playing)).run_if(
+                |mode: Res<Mode>, players: Res<Players>, autopilot: Res<Autopilot>| {
+                    matches!(*mode, Mode::Daily(_)) && players.0 == 1 && !autopilot.enabled
+                },
+            ),
+        );
+    }
+}
+
+//the best daily scores so far, the main menu shows these as a calendar
+#[derive(Resource, Deref)]
+pub struct Daily(pub DailyRecords);
+
+//says which day is being played and that day's best
+#[derive(Component)]
+struct DailyText;
+
+fn daily_label(mode: &Mode, daily: &Daily) -> String {
+    match mode {
+        Mode::Daily(date) => match daily.best(*date) {
+            Some(best) => format!("Daily {}  best {}", date, best),
+            None => format!("Daily {}", date),
+        },
+        _ => String::new(),
+    }
+}
+
+fn setup(mut commands: Commands, mode: Res<Mode>, daily: Res<Daily>) {
+    commands.spawn((
+        TextBundle::from_section(
+            daily_label(&mode, &daily),
+            TextStyle {
+                font_size: 40.,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.),
+            right: Val::Px(50.),
+            ..default()
+        }),
+        DailyText,
+        Game,
+    ));
+}
+
+//keeps the score if it's the best run of the day
+fn record_result(
+    mode: Res<Mode>,
+    score: Res<Score>,
+    mut daily: ResMut<Daily>,
+    mut text: Query<&mut Text, With<DailyText>>,
+) {
+    let Mode::Daily(date) = *mode else {
+        return;
+    };
+    if !daily.0.record(date, score.0[0]) {
+        return;
+    }
+    if let Err(err) = daily.save(storage::daily_records_path()) {
+        warn!("couldn't save daily records: {}", err);
+    }
+    for mut text in text.iter_mut() {
+        text.sections[0].value = format!("Daily {}  new best {}!", date, score.0[0]);
+    }
+}
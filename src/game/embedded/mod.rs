@@ -0,0 +1,177 @@
+use bevy::{
+    asset::io::{
+        file::FileAssetReader, AssetReader, AssetReaderError, AssetSource, AssetSourceId,
+        PathStream, Reader, VecReader,
+    },
+    prelude::*,
+    tasks::futures_lite::stream,
+    utils::BoxedFuture,
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+//built with --features embedded-assets the whole assets folder goes into the executable, so it runs
+//on its own without an assets folder next to it
+//anything that is on disk still wins so theme packs and swapped out sprites keep working
+pub struct EmbeddedAssetsPlugin;
+
+impl Plugin for EmbeddedAssetsPlugin {
+    //has to go in before DefaultPlugins sets up the asset server
+    fn build(&self, app: &mut App) {
+        app.register_asset_source(
+            AssetSourceId::Default,
+            AssetSource::build().with_reader(|| {
+                Box::new(EmbeddedReader {
+                    disk: AssetSource::get_default_reader(ASSETS.to_string())(),
+                })
+            }),
+        );
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/embedded_assets.rs"));
+
+const ASSETS: &str = "assets";
+
+//the same folder the asset server reads from, which isn't always the working directory
+fn assets_dir() -> PathBuf {
+    FileAssetReader::get_base_path().join(ASSETS)
+}
+
+//a path inside the assets folder the way the embedded list writes it
+fn key(path: &Path) -> String {
+    path.components()
+        .map(|part| part.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn embedded(path: &str) -> Option<&'static [u8]> {
+    FILES
+        .iter()
+        .find(|(name, _)| *name == path)
+        .map(|(_, bytes)| *bytes)
+}
+
+//whatever's directly inside a folder of the embedded assets
+fn embedded_children(dir: &str) -> Vec<String> {
+    let prefix = if dir.is_empty() {
+        String::new()
+    } else {
+        format!("{}/", dir)
+    };
+    let mut children: Vec<String> = FILES
+        .iter()
+        .filter_map(|(name, _)| name.strip_prefix(&prefix))
+        .filter_map(|rest| rest.split('/').next())
+        .map(|child| format!("{}{}", prefix, child))
+        .collect();
+    children.sort();
+    children.dedup();
+    children
+}
+
+//whether there's a file at a path in the assets, on disk or built in
+pub fn exists(path: &str) -> bool {
+    assets_dir().join(path).is_file() || embedded(path).is_some()
+}
+
+pub fn read_to_string(path: &str) -> io::Result<String> {
+    match fs::read_to_string(assets_dir().join(path)) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => match embedded(path) {
+            Some(bytes) => String::from_utf8(bytes.to_vec())
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            None => Err(err),
+        },
+        result => result,
+    }
+}
+
+//names of the folders inside a folder of the assets, on disk or built in
+pub fn folders(dir: &str) -> Vec<String> {
+    let mut folders: Vec<String> = fs::read_dir(assets_dir().join(dir))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    for child in embedded_children(dir) {
+        let is_folder = FILES
+            .iter()
+            .any(|(name, _)| name.starts_with(&format!("{}/", child)));
+        let name = child.rsplit('/').next().unwrap_or_default().to_string();
+        if is_folder && !folders.contains(&name) {
+            folders.push(name);
+        }
+    }
+    folders
+}
+
+//reads from the assets folder and falls back on the built in copy
+struct EmbeddedReader {
+    disk: Box<dyn AssetReader>,
+}
+
+impl AssetReader for EmbeddedReader {
+    fn read<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Box<Reader<'a>>, AssetReaderError>> {
+        Box::pin(async move {
+            match self.disk.read(path).await {
+                Err(AssetReaderError::NotFound(missing)) => match embedded(&key(path)) {
+                    Some(bytes) => {
+                        let reader: Box<Reader> = Box::new(VecReader::new(bytes.to_vec()));
+                        Ok(reader)
+                    }
+                    None => Err(AssetReaderError::NotFound(missing)),
+                },
+                result => result,
+            }
+        })
+    }
+
+    //nothing built in has a .meta file
+    fn read_meta<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Box<Reader<'a>>, AssetReaderError>> {
+        self.disk.read_meta(path)
+    }
+
+    fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Box<PathStream>, AssetReaderError>> {
+        Box::pin(async move {
+            match self.disk.read_directory(path).await {
+                Err(AssetReaderError::NotFound(missing)) => {
+                    let children = embedded_children(&key(path));
+                    if children.is_empty() {
+                        return Err(AssetReaderError::NotFound(missing));
+                    }
+                    let children: Box<PathStream> =
+                        Box::new(stream::iter(children.into_iter().map(PathBuf::from)));
+                    Ok(children)
+                }
+                result => result,
+            }
+        })
+    }
+
+    fn is_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<bool, AssetReaderError>> {
+        Box::pin(async move {
+            if let Ok(true) = self.disk.is_directory(path).await {
+                return Ok(true);
+            }
+            let prefix = format!("{}/", key(path));
+            Ok(FILES.iter().any(|(name, _)| name.starts_with(&prefix)))
+        })
+    }
+}
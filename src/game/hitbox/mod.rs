@@ -0,0 +1,108 @@
+use crate::game::{
+    settings::Settings, start_simulation, theme::Theme, versus::Versus, Mode, Simulation,
+};
+use crate::{loading::GameAssets, AppState};
+use bevy::{prelude::*, render::render_resource::TextureFormat};
+use flappybird::sim::{
+    hitbox::{Hitboxes, Hull},
+    Rules,
+};
+
+//traced hitboxes from the settings, the bird and pipes collide as the shape they're drawn
+//instead of a circle and boxes
+pub struct HitboxPlugin;
+
+impl Plugin for HitboxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(AppState::Playing),
+            trace_hitboxes.after(start_simulation),
+        );
+    }
+}
+
+//one byte a pixel going across then down
+fn alpha(image: &Image) -> Option<Vec<u8>> {
+    let alpha = |data: &[u8]| data.chunks(4).map(|pixel| pixel[3]).collect();
+    match image.texture_descriptor.format {
+        TextureFormat::Rgba8UnormSrgb | TextureFormat::Rgba8Unorm => Some(alpha(&image.data)),
+        _ => image
+            .convert(TextureFormat::Rgba8UnormSrgb)
+            .map(|image| alpha(&image.data)),
+    }
+}
+
+//the theme's outline if it has one, traced from the picture if not
+//in the picture's pixels from its middle, y up
+fn outline(image: &Image, hull: &Option<Hull>, threshold: u8) -> Result<Hull, String> {
+    if let Some(hull) = hull {
+        return Ok(hull.clone());
+    }
+    let alpha = alpha(image).ok_or("it isn't a picture format that has alpha")?;
+    Hull::from_alpha(image.width(), image.height(), &alpha, threshold, 0.)
+}
+
+//from the picture's pixels to the world around the point the sprite is anchored on
+fn place(hull: &Hull, image: &Image, anchor: [f32; 2], scale: Vec2) -> Hull {
+    let (width, height) = (image.width() as f32, image.height() as f32);
+    hull.offset(anchor[0] * width, anchor[1] * height)
+        .scaled(scale.x, scale.y)
+}
+
+fn hitboxes(
+    theme: &Theme,
+    rules: &Rules,
+    images: &Assets<Image>,
+    assets: &GameAssets,
+    settings: &Settings,
+) -> Result<Hitboxes, String> {
+    let config = settings.hitboxes;
+    let bird_image = images.get(&assets.bird).ok_or("the bird isn't loaded")?;
+    let pipe_image = images.get(&assets.pipe).ok_or("the pipe isn't loaded")?;
+
+    let bird = outline(bird_image, &theme.bird.hull, config.threshold)?;
+    let size = theme.bird.size(Rules::default().bird_radius);
+    let bird = place(
+        &bird,
+        bird_image,
+        theme.bird.anchor,
+        size / bird_image.size().as_vec2(),
+    );
+    let pipe = outline(pipe_image, &theme.pipe.hull, config.threshold)?;
+    let scale = theme
+        .pipe
+        .scale(rules.pipe_half_width, rules.pipe_half_height);
+    let pipe = place(&pipe, pipe_image, theme.pipe.anchor, scale.truncate());
+
+    //forgiveness is in world pixels so it feels the same whatever size the pictures are
+    let inset = |hull: Hull, what: &str| {
+        hull.inset(config.forgiveness).ok_or(format!(
+            "a forgiveness of {} leaves no {}",
+            config.forgiveness, what
+        ))
+    };
+    Ok(Hitboxes {
+        bird: inset(bird, "bird")?,
+        pipe: inset(pipe, "pipe")?,
+    })
+}
+
+//only standard runs, a versus opponent or a shared code wouldn't have the same shapes
+fn trace_hitboxes(
+    mut simulation: ResMut<Simulation>,
+    theme: Res<Theme>,
+    images: Res<Assets<Image>>,
+    assets: Res<GameAssets>,
+    settings: Res<Settings>,
+    mode: Res<Mode>,
+    versus: Option<Res<Versus>>,
+) {
+    if !settings.hitboxes.traced || *mode != Mode::Standard || versus.is_some() {
+        return;
+    }
+    let rules = simulation.rules;
+    match hitboxes(&theme, &rules, &images, &assets, &settings) {
+        Ok(hitboxes) => simulation.set_hitboxes(Some(hitboxes)),
+        Err(err) => warn!("couldn't trace hitboxes, using the usual ones: {}", err),
+    }
+}
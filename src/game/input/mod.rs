@@ -0,0 +1,425 @@
+use crate::game::{settings::Settings, MAX_PLAYERS};
+use bevy::{
+    ecs::system::SystemParam,
+    input::InputSystem,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use flappybird::config::{Config, ControlsConfig};
+
+//turns whatever the player pressed into the actions the game cares about, so every system
+//asks whether to flap or go back instead of which key was pressed
+pub struct InputPlugin;
+
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Bindings>()
+            .init_resource::<Actions>()
+            .add_systems(
+                PreUpdate,
+                (
+                    load_bindings.run_if(resource_changed::<Settings>),
+                    update_actions,
+                )
+                    .chain()
+                    .in_set(UpdateActions)
+                    .after(InputSystem),
+            );
+    }
+}
+
+//anything reading Actions in PreUpdate goes after this
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct UpdateActions;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    //for each player sharing the computer
+    Flap(usize),
+    Pause,
+    Confirm,
+    Back,
+    //moving around the menus
+    Up,
+    Down,
+    Left,
+    Right,
+    //the challenge code at the end of a run
+    Copy,
+}
+
+//one thing that can be pressed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Pad(GamepadButtonType),
+}
+
+impl Binding {
+    //what it's saved as in the config, keys by the names bevy gives them
+    pub fn name(self) -> String {
+        match self {
+            Binding::Key(key) => format!("{:?}", key),
+            Binding::Mouse(button) => format!("Mouse{:?}", button),
+            Binding::Pad(button) => format!("Pad{:?}", button),
+        }
+    }
+
+    //none for names that aren't something that can be bound
+    pub fn parse(name: &str) -> Option<Binding> {
+        KEYS.into_iter()
+            .map(Binding::Key)
+            .chain(MOUSE_BUTTONS.into_iter().map(Binding::Mouse))
+            .chain(PAD_BUTTONS.into_iter().map(Binding::Pad))
+            .find(|binding| binding.name() == name)
+    }
+
+    fn same_kind(self, other: Binding) -> bool {
+        matches!(
+            (self, other),
+            (Binding::Key(_), Binding::Key(_))
+                | (Binding::Mouse(_), Binding::Mouse(_))
+                | (Binding::Pad(_), Binding::Pad(_))
+        )
+    }
+}
+
+//what each action is bound to, read from the settings whenever they change
+#[derive(Resource, Default)]
+pub struct Bindings(HashMap<Action, Vec<Binding>>);
+
+impl Bindings {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.0.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    //the first thing bound to it, for telling the player what to press
+    pub fn name(&self, action: Action) -> String {
+        self.get(action)
+            .first()
+            .map_or("nothing".to_string(), |binding| binding.name())
+    }
+}
+
+//the actions that were pressed this frame
+#[derive(Resource, Default)]
+pub struct Actions(HashSet<Action>);
+
+impl Actions {
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.0.contains(&action)
+    }
+}
+
+//the keys and buttons underneath the actions
+#[derive(SystemParam)]
+pub struct Buttons<'w> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    pads: Res<'w, ButtonInput<GamepadButton>>,
+    gamepads: Res<'w, Gamepads>,
+}
+
+impl Buttons<'_> {
+    //player's flap only listens to their own gamepad, the order they were connected in
+    //everything else listens to all of them
+    fn gamepads(&self, action: Action) -> Vec<Gamepad> {
+        let mut gamepads: Vec<Gamepad> = self.gamepads.iter().collect();
+        gamepads.sort_by_key(|gamepad| gamepad.id);
+        match action {
+            Action::Flap(player) => gamepads.into_iter().skip(player).take(1).collect(),
+            _ => gamepads,
+        }
+    }
+
+    fn just_pressed(&self, binding: Binding, gamepads: &[Gamepad]) -> bool {
+        match binding {
+            Binding::Key(key) => self.keys.just_pressed(key),
+            Binding::Mouse(button) => self.mouse.just_pressed(button),
+            Binding::Pad(button) => gamepads
+                .iter()
+                .any(|gamepad| self.pads.just_pressed(GamepadButton::new(*gamepad, button))),
+        }
+    }
+
+    //a key or gamepad button pressed this frame that can be bound
+    //the mouse is left out as clicking is how the menus get used
+    pub fn any_just_pressed(&self) -> Option<Binding> {
+        self.keys
+            .get_just_pressed()
+            .copied()
+            .filter(|key| KEYS.contains(key))
+            .map(Binding::Key)
+            .chain(
+                self.pads
+                    .get_just_pressed()
+                    .map(|button| button.button_type)
+                    .filter(|button| PAD_BUTTONS.contains(button))
+                    .map(Binding::Pad),
+            )
+            .next()
+    }
+}
+
+fn parse_all(names: &[String]) -> Vec<Binding> {
+    names
+        .iter()
+        .filter_map(|name| {
+            let binding = Binding::parse(name);
+            if binding.is_none() {
+                warn!("{} isn't something that can be bound", name);
+            }
+            binding
+        })
+        .collect()
+}
+
+fn load_bindings(settings: Res<Settings>, mut bindings: ResMut<Bindings>) {
+    let controls = &settings.controls;
+    let defaults = ControlsConfig::default();
+    //older files might not have every player
+    let mut map: HashMap<Action, Vec<Binding>> = (0..MAX_PLAYERS)
+        .map(|player| {
+            let names = controls.flap.get(player).unwrap_or(&defaults.flap[player]);
+            (Action::Flap(player), parse_all(names))
+        })
+        .collect();
+    map.insert(Action::Pause, parse_all(&controls.pause));
+    map.insert(Action::Confirm, parse_all(&controls.confirm));
+    map.insert(Action::Back, parse_all(&controls.back));
+    map.insert(Action::Up, parse_all(&controls.up));
+    map.insert(Action::Down, parse_all(&controls.down));
+    map.insert(Action::Left, parse_all(&controls.left));
+    map.insert(Action::Right, parse_all(&controls.right));
+    map.insert(Action::Copy, parse_all(&controls.copy));
+    bindings.0 = map;
+}
+
+fn update_actions(buttons: Buttons, bindings: Res<Bindings>, mut actions: ResMut<Actions>) {
+    actions.0.clear();
+    for (action, bound) in bindings.0.iter() {
+        let gamepads = buttons.gamepads(*action);
+        if bound
+            .iter()
+            .any(|binding| buttons.just_pressed(*binding, &gamepads))
+        {
+            actions.0.insert(*action);
+        }
+    }
+}
+
+//a player's flap bindings with this one swapped in for whatever they had on the same device
+//if another player had it they get the old one instead so no two players flap on the same key
+pub fn rebind(config: &mut Config, player: usize, binding: Binding) {
+    let flap = &mut config.controls.flap;
+    if flap.len() < MAX_PLAYERS {
+        flap.extend_from_slice(&ControlsConfig::default().flap[flap.len()..]);
+    }
+    let name = binding.name();
+    let names = &mut flap[player];
+    //the first one on the same device is swapped and any others dropped
+    let mut old = None;
+    names.retain_mut(|bound| {
+        if !Binding::parse(bound).is_some_and(|bound| bound.same_kind(binding)) {
+            return true;
+        }
+        if old.is_some() {
+            return false;
+        }
+        old = Some(std::mem::replace(bound, name.clone()));
+        true
+    });
+    if old.is_none() {
+        names.push(name.clone());
+    }
+    //everyone flaps with the south button on their own gamepad so those can be shared
+    if matches!(binding, Binding::Pad(_)) {
+        return;
+    }
+    for (other, names) in flap.iter_mut().enumerate() {
+        if other == player {
+            continue;
+        }
+        let Some(index) = names.iter().position(|bound| *bound == name) else {
+            continue;
+        };
+        match &old {
+            Some(old) if *old != name && !names.contains(old) => names[index].clone_from(old),
+            _ => {
+                names.remove(index);
+            }
+        }
+    }
+}
+
+//keys that can be bound
+const KEYS: [KeyCode; 73] = [
+    KeyCode::Escape,
+    KeyCode::Space,
+    KeyCode::Enter,
+    KeyCode::Tab,
+    KeyCode::Backspace,
+    KeyCode::ShiftLeft,
+    KeyCode::ShiftRight,
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+    KeyCode::AltLeft,
+    KeyCode::AltRight,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::Numpad0,
+    KeyCode::Numpad1,
+    KeyCode::Numpad2,
+    KeyCode::Numpad3,
+    KeyCode::Numpad4,
+    KeyCode::Numpad5,
+    KeyCode::Numpad6,
+    KeyCode::Numpad7,
+    KeyCode::Numpad8,
+    KeyCode::Numpad9,
+    KeyCode::NumpadEnter,
+    KeyCode::Comma,
+    KeyCode::Period,
+    KeyCode::Slash,
+    KeyCode::Semicolon,
+    KeyCode::Quote,
+    KeyCode::BracketLeft,
+    KeyCode::BracketRight,
+    KeyCode::Minus,
+    KeyCode::Equal,
+    KeyCode::Backslash,
+    KeyCode::Backquote,
+];
+
+const MOUSE_BUTTONS: [MouseButton; 3] =
+    [MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+
+const PAD_BUTTONS: [GamepadButtonType; 19] = [
+    GamepadButtonType::South,
+    GamepadButtonType::East,
+    GamepadButtonType::North,
+    GamepadButtonType::West,
+    GamepadButtonType::C,
+    GamepadButtonType::Z,
+    GamepadButtonType::LeftTrigger,
+    GamepadButtonType::LeftTrigger2,
+    GamepadButtonType::RightTrigger,
+    GamepadButtonType::RightTrigger2,
+    GamepadButtonType::Select,
+    GamepadButtonType::Start,
+    GamepadButtonType::Mode,
+    GamepadButtonType::LeftThumb,
+    GamepadButtonType::RightThumb,
+    GamepadButtonType::DPadUp,
+    GamepadButtonType::DPadDown,
+    GamepadButtonType::DPadLeft,
+    GamepadButtonType::DPadRight,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flaps(config: &Config) -> Vec<Vec<&str>> {
+        config
+            .controls
+            .flap
+            .iter()
+            .map(|names| names.iter().map(String::as_str).collect())
+            .collect()
+    }
+
+    //menu movement only happens off a run so sharing with that is fine
+    #[test]
+    fn default_flap_keys_arent_bound_to_anything_read_during_a_run() {
+        let controls = ControlsConfig::default();
+        let others = [&controls.pause, &controls.confirm, &controls.back];
+        for names in controls.flap.iter() {
+            let key = names
+                .iter()
+                .find(|name| matches!(Binding::parse(name), Some(Binding::Key(_))))
+                .unwrap();
+            assert!(others.iter().all(|other| !other.contains(key)), "{}", key);
+        }
+    }
+
+    #[test]
+    fn taking_another_players_key_swaps_them() {
+        let mut config = Config::default();
+        rebind(&mut config, 0, Binding::Key(KeyCode::KeyW));
+        assert_eq!(flaps(&config)[0], ["KeyW", "MouseLeft", "PadSouth"]);
+        assert_eq!(flaps(&config)[1], ["Space", "PadSouth"]);
+    }
+
+    #[test]
+    fn swapping_back_puts_both_players_back() {
+        let mut config = Config::default();
+        rebind(&mut config, 0, Binding::Key(KeyCode::KeyW));
+        rebind(&mut config, 0, Binding::Key(KeyCode::Space));
+        assert_eq!(flaps(&config), flaps(&Config::default()));
+    }
+
+    #[test]
+    fn rebinding_a_key_already_bound_changes_nothing() {
+        let mut config = Config::default();
+        rebind(&mut config, 0, Binding::Key(KeyCode::Space));
+        assert_eq!(flaps(&config), flaps(&Config::default()));
+    }
+
+    #[test]
+    fn taken_binding_with_nothing_to_swap_is_dropped() {
+        let mut config = Config::default();
+        rebind(&mut config, 1, Binding::Mouse(MouseButton::Left));
+        assert_eq!(flaps(&config)[0], ["Space", "PadSouth"]);
+        assert_eq!(flaps(&config)[1], ["KeyW", "PadSouth", "MouseLeft"]);
+    }
+
+    #[test]
+    fn gamepad_buttons_stay_shared() {
+        let mut config = Config::default();
+        rebind(&mut config, 0, Binding::Pad(GamepadButtonType::North));
+        assert_eq!(flaps(&config)[0], ["Space", "MouseLeft", "PadNorth"]);
+        rebind(&mut config, 1, Binding::Pad(GamepadButtonType::North));
+        assert_eq!(flaps(&config)[0], ["Space", "MouseLeft", "PadNorth"]);
+        assert_eq!(flaps(&config)[1], ["KeyW", "PadNorth"]);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::game::{autopilot::Autopilot, GameState, Players, Recording, Score, Simulation};
+use crate::AppState;
+use bevy::{
+    prelude::*,
+    tasks::{block_on, poll_once, IoTaskPool, Task},
+};
+use flappybird::{
+    leaderboard::{self, Client, Entry, Submission},
+    storage,
+};
+
+//how many scores the main menu shows
+pub const TOP_SCORES: usize = 10;
+
+//talks to the online leaderboard in the background so the game never waits on the network
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TopScores>()
+            .init_resource::<LeaderboardTasks>()
+            //anything left over from last time the server couldn't be reached
+            .add_systems(Startup, |mut tasks: ResMut<LeaderboardTasks>| {
+                tasks.submit(None)
+            })
+            .add_systems(OnEnter(AppState::MainMenu), fetch_top_scores)
+            .add_systems(
+                OnEnter(GameState::Dead),
+                submit_score.run_if(in_state(AppState::Playing)).run_if(
+                    |players: Res<Players>,
+                     autopilot: Res<Autopilot>,
+                     simulation: Res<Simulation>,
+                     recording: Res<Recording>| {
+                        //the server checks runs with the usual hitboxes so traced ones would fail
+                        players.0 == 1
+                            && !autopilot.enabled
+                            && simulation.hitboxes().is_none()
+                            && leaderboard::ranked(&recording)
+                    },
+                ),
+            )
+            .add_systems(Update, poll_tasks);
+    }
+}
+
+//the best scores on the leaderboard for the main menu
+#[derive(Resource, Default)]
+pub enum TopScores {
+    #[default]
+    Loading,
+    Loaded(Vec<Entry>),
+    Offline,
+}
+
+#[derive(Resource, Default)]
+struct LeaderboardTasks {
+    fetch: Option<Task<Result<Vec<Entry>, leaderboard::Error>>>,
+    //only one at a time since each one rewrites the queue file
+    submit: Option<Task<Result<usize, leaderboard::Error>>>,
+    //waiting for the current submit to finish
+    waiting: Vec<Submission>,
+    flush: bool,
+}
+
+impl LeaderboardTasks {
+    //sends the submission along with anything still queued, it gets queued itself if that fails
+    fn submit(&mut self, submission: Option<Submission>) {
+        self.waiting.extend(submission);
+        self.flush = true;
+        self.start_submit();
+    }
+
+    fn start_submit(&mut self) {
+        if self.submit.is_some() || !self.flush {
+            return;
+        }
+        self.flush = false;
+        let submissions = std::mem::take(&mut self.waiting);
+        self.submit = Some(IoTaskPool::get().spawn(async move {
+            Client::from_env().submit_queued(submissions, &storage::leaderboard_queue_path())
+        }));
+    }
+
+    fn fetch(&mut self) {
+        self.fetch =
+            Some(IoTaskPool::get().spawn(async move { Client::from_env().top(TOP_SCORES) }));
+    }
+}
+
+fn fetch_top_scores(mut tasks: ResMut<LeaderboardTasks>) {
+    tasks.fetch();
+}
+
+//sends a finished solo run with its replay so the server can check it
+fn submit_score(mut tasks: ResMut<LeaderboardTasks>, score: Res<Score>, recording: Res<Recording>) {
+    tasks.submit(Some(Submission {
+        name: leaderboard::player_name(),
+        score: score.0[0],
+        replay: recording.to_string(),
+    }));
+}
+
+fn poll_tasks(mut tasks: ResMut<LeaderboardTasks>, mut top_scores: ResMut<TopScores>) {
+    if let Some(result) = tasks
+        .fetch
+        .as_mut()
+        .and_then(|task| block_on(poll_once(task)))
+    {
+        tasks.fetch = None;
+        *top_scores = match result {
+            Ok(entries) => TopScores::Loaded(entries),
+            Err(err) => {
+                info!("couldn't fetch top scores: {}", err);
+                TopScores::Offline
+            }
+        };
+    }
+    let mut sent = false;
+    if let Some(result) = tasks
+        .submit
+        .as_mut()
+        .and_then(|task| block_on(poll_once(task)))
+    {
+        tasks.submit = None;
+        match result {
+            Ok(count) => sent = count > 0,
+            Err(err) => info!("couldn't send scores, they'll be sent next time: {}", err),
+        }
+        tasks.start_submit();
+    }
+    //the top scores might have changed, fetch them again so the menu is up to date
+    if sent {
+        tasks.fetch();
+    }
+}
@@ -1,12 +1,35 @@
+mod audio;
+pub mod autopilot;
 mod bird;
+mod camera;
 use bird::{Bird, BirdPlugin};
+mod challenge;
 mod cloud;
+pub mod daily;
+mod embedded;
+mod hitbox;
+pub mod input;
+pub mod leaderboard;
+pub mod parallax;
+mod particles;
+mod pause;
 mod pipe_spawner;
+pub mod settings;
+pub mod sky;
+pub mod theme;
 mod ui;
+pub mod versus;
+mod weather;
 
 use crate::AppState;
 use bevy::{math::f32, prelude::*, window::WindowResolution};
-use bevy_rapier2d::prelude::*;
+use flappybird::{
+    challenge::{Challenge, Course},
+    daily::{Date, DIFFICULTY as DAILY_DIFFICULTY},
+    sim::{self, replay::Replay, Difficulty, Mutators, World},
+    storage,
+};
+use rand::{thread_rng, Rng};
 
 //Creates a game state so that we can keep track of if the bird is dead
 //Probably could've just used a component
@@ -20,9 +43,18 @@ pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Score(0))
+        app.insert_resource(Score(vec![0]))
+            .insert_resource(Players(1))
             .insert_state(GameState::Playing)
             .insert_resource(GameOverTimer::default())
+            .init_resource::<Simulation>()
+            .init_resource::<Recording>()
+            .init_resource::<NextSeed>()
+            .init_resource::<Mode>()
+            .init_resource::<SelectedMutators>()
+            .add_event::<SimulationEvent>()
+            .insert_resource(Time::<Fixed>::from_hz(sim::TICK_RATE as f64))
+            .add_plugins(embedded::EmbeddedAssetsPlugin)
             .add_plugins((
                 DefaultPlugins.set(WindowPlugin {
                     primary_window: Some(Window {
@@ -35,18 +67,45 @@ impl Plugin for GamePlugin {
                     ..default()
                 }),
                 BirdPlugin,
+                autopilot::AutopilotPlugin,
                 ui::UiPlugin,
                 pipe_spawner::PipeSpawnerPlugin,
-                cloud::CloudPlugin,
-                RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0),
+                //everything in the background
+                (
+                    cloud::CloudPlugin,
+                    parallax::ParallaxPlugin,
+                    sky::SkyPlugin,
+                    weather::WeatherPlugin,
+                ),
+                versus::VersusPlugin,
+                leaderboard::LeaderboardPlugin,
+                daily::DailyPlugin,
+                challenge::ChallengePlugin,
+                (
+                    settings::SettingsPlugin,
+                    input::InputPlugin,
+                    theme::ThemePlugin,
+                    hitbox::HitboxPlugin,
+                ),
+                audio::AudioPlugin,
+                //juice on top of what the simulation does
+                (particles::ParticlesPlugin, camera::CameraPlugin),
+                pause::PausePlugin,
             ))
             .add_systems(
                 OnEnter(AppState::Playing),
                 (
-                    set_sky_colour,
+                    start_simulation,
                     reset_game_state.run_if(in_state(GameState::Dead)),
                 ),
             )
+            .add_systems(
+                FixedUpdate,
+                step_simulation
+                    .in_set(StepSimulation)
+                    .run_if(not(resource_exists::<versus::Versus>))
+                    .run_if(in_state(AppState::Playing)),
+            )
             .add_systems(
                 Update,
                 delete_offscreen_entities
@@ -55,9 +114,7 @@ impl Plugin for GamePlugin {
             )
             .add_systems(
                 Update,
-                bird_pipe_collide
-                    .run_if(in_state(AppState::Playing))
-                    .run_if(in_state(GameState::Playing)),
+                bird_pipe_collide.run_if(in_state(AppState::Playing)),
             )
             .add_systems(
                 Update,
@@ -69,13 +126,100 @@ impl Plugin for GamePlugin {
     }
 }
 
-//Score component keeping an unsigned 32bit int cuz Score can't be decreased
+//Score component keeping an unsigned 32bit int per player cuz Score can't be decreased
+#[derive(Resource, Deref, DerefMut, Clone)]
+pub struct Score(pub Vec<u32>);
+
+//seed for the next run when it has to be a particular course (like a versus match), random otherwise
+#[derive(Resource, Default)]
+pub struct NextSeed(pub Option<u64>);
+
+//how many birds are playing on this keyboard, picked on the main menu
 #[derive(Resource, Deref, DerefMut, Clone, Copy)]
-pub struct Score(u32);
+pub struct Players(pub usize);
 
-//sets the background colour to sky blue
-fn set_sky_colour(mut sky_colour: ResMut<ClearColor>) {
-    sky_colour.0 = Color::rgb_u8(135, 206, 235);
+pub const MAX_PLAYERS: usize = 4;
+
+//what kind of run is being played, picked on the main menu
+#[derive(Resource, Default, Clone, Copy, PartialEq, Debug)]
+pub enum Mode {
+    #[default]
+    Standard,
+    //that day's course, the date is kept so a run started just before midnight counts for that day
+    Daily(Date),
+    //exactly the run a pasted challenge code describes
+    Challenge(Challenge),
+}
+
+//mutators for standard runs, picked on the main menu
+#[derive(Resource, Default, Deref, DerefMut, Clone, Copy)]
+pub struct SelectedMutators(pub Mutators);
+
+//the headless simulation that actually plays the game, everything on screen just follows it
+#[derive(Resource, Deref, DerefMut)]
+pub struct Simulation(pub World);
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Simulation(World::new(Difficulty::default().rules(), 0, 1))
+    }
+}
+
+//the flaps of the current run so it can be saved as a replay when the bird dies
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct Recording(pub Replay);
+
+//anything the simulation reports during a step gets sent on as one of these
+#[derive(Event, Deref, Clone, Copy)]
+pub struct SimulationEvent(pub sim::Event);
+
+//systems that feed the simulation (like bird::jump) go before this
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct StepSimulation;
+
+//starts a fresh run with a bird for every player, on a random seed unless the mode says otherwise
+#[allow(clippy::too_many_arguments)]
+fn start_simulation(
+    mut simulation: ResMut<Simulation>,
+    mut recording: ResMut<Recording>,
+    mut score: ResMut<Score>,
+    mut current: ResMut<challenge::CurrentChallenge>,
+    players: Res<Players>,
+    mut next_seed: ResMut<NextSeed>,
+    mode: Res<Mode>,
+    mutators: Res<SelectedMutators>,
+    versus: Option<Res<versus::Versus>>,
+) {
+    let challenge = match *mode {
+        Mode::Daily(date) => Challenge {
+            course: Course::Daily(date),
+            difficulty: DAILY_DIFFICULTY,
+            mutators: Mutators::NONE,
+        },
+        Mode::Challenge(challenge) => challenge,
+        Mode::Standard => Challenge {
+            course: Course::Seed(next_seed.0.take().unwrap_or_else(|| thread_rng().gen())),
+            difficulty: Difficulty::default(),
+            //the other side of a versus match doesn't know what we picked
+            mutators: if versus.is_some() {
+                Mutators::NONE
+            } else {
+                mutators.0
+            },
+        },
+    };
+    simulation.0 = World::new(challenge.rules(), challenge.seed(), players.0);
+    recording.0 =
+        Replay::new(challenge.seed(), challenge.difficulty).with_mutators(challenge.mutators);
+    score.0 = vec![0; players.0];
+    current.0 = challenge;
+}
+
+//steps the simulation on the fixed tick and passes on what happened
+fn step_simulation(mut simulation: ResMut<Simulation>, mut events: EventWriter<SimulationEvent>) {
+    for event in simulation.step() {
+        events.send(SimulationEvent(*event));
+    }
 }
 
 //resets the game state so the bird doesn't instantly die when you replay
@@ -83,14 +227,23 @@ fn reset_game_state(mut game_state: ResMut<NextState<GameState>>) {
     game_state.set(GameState::Playing);
 }
 
-//util function to convert degrees to radians, didn't use pi because this is already enough precision
+//util function to convert degrees to radians
 pub fn degrees_to_radians(deg: f32) -> f32 {
-    deg * (3.14159265 / 180.)
+    deg * (std::f32::consts::PI / 180.)
 }
 
 //deletes all entities thats x pos exists past -1200
+//pipes are left alone as the simulation removes those itself
+#[allow(clippy::type_complexity)]
 fn delete_offscreen_entities(
-    entities: Query<(Entity, &Transform), Without<Bird>>,
+    entities: Query<
+        (Entity, &Transform),
+        (
+            Without<Bird>,
+            Without<pipe_spawner::PipeGap>,
+            Without<parallax::Tile>,
+        ),
+    >,
     mut commands: Commands,
 ) {
     for (entity, transform) in entities.iter() {
@@ -100,32 +253,31 @@ fn delete_offscreen_entities(
     }
 }
 
-//Does both bird collides, should probably split this into two functions
+//Does both bird collides, the simulation works out the hits and we just apply them
 fn bird_pipe_collide(
-    bird: Query<Entity, With<Bird>>,
-    pipes: Query<Entity, With<pipe_spawner::Pipe>>,
-    mut pipe_gaps: Query<(Entity, &mut pipe_spawner::PipeGap)>,
-    rapier_context: Res<RapierContext>,
+    mut events: EventReader<SimulationEvent>,
+    simulation: Res<Simulation>,
+    recording: Res<Recording>,
     mut game_state: ResMut<NextState<GameState>>,
     mut score: ResMut<Score>,
 ) {
-    for bird in bird.iter() {
-        //sets the game state to dead if you collide with a pipe collider
-        for pipe in pipes.iter() {
-            if let Some(contact_pair) = rapier_context.contact_pair(bird, pipe) {
-                if contact_pair.has_any_active_contacts() {
-                    game_state.set(GameState::Dead)
+    for event in events.read() {
+        match **event {
+            //sets the game state to dead once nobody is left flying and keeps solo runs as a replay
+            //unless they had traced hitboxes, a replay can't play those back
+            sim::Event::Died { .. } if simulation.all_dead() => {
+                game_state.set(GameState::Dead);
+                if simulation.birds().len() == 1 && simulation.hitboxes().is_none() {
+                    if let Err(err) = recording.save(storage::last_replay_path()) {
+                        warn!("couldn't save replay: {}", err);
+                    }
                 }
             }
-        }
-        //increases the score when you manage to pass through the pipegap
-        for (pipe_gap, mut pipe_gap_interaction) in pipe_gaps.iter_mut() {
-            if rapier_context.intersection_pair(bird, pipe_gap) == Some(true)
-                && pipe_gap_interaction.interacted == false
-            {
-                pipe_gap_interaction.interacted = true;
-                score.0 += 1;
+            //increases that player's score when they manage to pass through the pipegap
+            sim::Event::Scored { bird } => {
+                score.0[bird] = simulation.birds()[bird].score;
             }
+            _ => (),
         }
     }
 }
@@ -0,0 +1,308 @@
+use crate::game::{sky::Tint, Game, Simulation};
+use crate::{loading::GameAssets, AppState};
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+//scenery behind (and a bit in front of) the pipes, each layer scrolling at its own fraction
+//of the pipe speed so the far ones look far away
+pub struct ParallaxPlugin;
+
+impl Plugin for ParallaxPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ParallaxLayers>()
+            .add_systems(OnEnter(AppState::Playing), spawn_strips)
+            .add_systems(
+                Update,
+                (scroll, wrap_strips)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+//half of what the camera can see across
+pub const VIEW_HALF_WIDTH: f32 = 960.;
+//bottom of the screen
+const GROUND: f32 = -540.;
+
+//the layers for the current look of the game, a theme can swap in its own
+#[derive(Resource, Clone, Debug, Deref, DerefMut, Serialize, Deserialize)]
+pub struct ParallaxLayers(pub Vec<Layer>);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Layer {
+    pub name: String,
+    //how fast it moves compared to the pipes, 0 stands still and 1 keeps up with them
+    pub scroll: f32,
+    //pipes and the bird are at 0
+    pub z: f32,
+    //rgba from 0 to 1, the whole layer is tinted by it
+    pub colour: [f32; 4],
+    pub kind: LayerKind,
+}
+
+impl Layer {
+    pub fn colour(&self) -> Color {
+        let [red, green, blue, alpha] = self.colour;
+        Color::rgba(red, green, blue, alpha)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum LayerKind {
+    //one tile repeated end to end along the screen, y is where its bottom edge sits
+    Strip {
+        shape: Shape,
+        width: f32,
+        height: f32,
+        #[serde(default = "ground")]
+        y: f32,
+    },
+    //sprites dotted about at random, spawned by the cloud plugin
+    Clouds {
+        image: String,
+        scale: f32,
+        //chance of one turning up every time the cloud timer goes off
+        chance: f32,
+    },
+}
+
+fn ground() -> f32 {
+    GROUND
+}
+
+//what a strip looks like, all the built in ones tile seamlessly
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Shape {
+    Hills,
+    Skyline,
+    Bushes,
+    //a picture from the assets folder, it has to tile itself
+    Image(String),
+}
+
+impl Default for ParallaxLayers {
+    fn default() -> Self {
+        let strip = |shape, width, height| LayerKind::Strip {
+            shape,
+            width,
+            height,
+            y: GROUND,
+        };
+        let clouds = |scale, chance| LayerKind::Clouds {
+            image: "Sprites/cloud.png".to_string(),
+            scale,
+            chance,
+        };
+        ParallaxLayers(vec![
+            Layer {
+                name: "hills".to_string(),
+                scroll: 0.1,
+                z: -40.,
+                colour: [0.56, 0.74, 0.68, 1.],
+                kind: strip(Shape::Hills, 1280., 380.),
+            },
+            Layer {
+                name: "skyline".to_string(),
+                scroll: 0.25,
+                z: -30.,
+                colour: [0.62, 0.68, 0.78, 1.],
+                kind: strip(Shape::Skyline, 1024., 300.),
+            },
+            //the two cloud sizes the game always had
+            Layer {
+                name: "far clouds".to_string(),
+                scroll: 0.625,
+                z: -15.,
+                colour: [1., 1., 1., 0.3],
+                kind: clouds(0.4, 1. / 3.),
+            },
+            Layer {
+                name: "near clouds".to_string(),
+                scroll: 0.75,
+                z: -10.,
+                colour: [1., 1., 1., 0.5],
+                kind: clouds(0.2, 1. / 3.),
+            },
+            //in front of the pipes so it moves faster than them
+            Layer {
+                name: "bushes".to_string(),
+                scroll: 1.2,
+                z: 5.,
+                colour: [0.25, 0.55, 0.25, 1.],
+                kind: strip(Shape::Bushes, 768., 70.),
+            },
+        ])
+    }
+}
+
+//moves with the world at this fraction of the pipe speed
+#[derive(Component)]
+pub struct Scroll(pub f32);
+
+//one tile of a strip, it jumps back to the far end once it's gone off the left
+#[derive(Component)]
+pub struct Tile {
+    width: f32,
+    //how many tiles make up the strip
+    count: usize,
+}
+
+fn spawn_strips(
+    mut commands: Commands,
+    layers: Res<ParallaxLayers>,
+    assets: Res<GameAssets>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for layer in layers.iter() {
+        let LayerKind::Strip {
+            shape,
+            width,
+            height,
+            y,
+        } = &layer.kind
+        else {
+            continue;
+        };
+        let (width, height) = (width.max(1.), height.max(1.));
+        let texture = match shape {
+            Shape::Image(path) => assets.image(path),
+            shape => images.add(draw(shape, width as u32, height as u32)),
+        };
+        //enough to cover the screen with one spare sliding in from the right
+        let count = (VIEW_HALF_WIDTH * 2. / width).ceil() as usize + 1;
+        for i in 0..count {
+            commands.spawn((
+                SpriteBundle {
+                    texture: texture.clone(),
+                    sprite: Sprite {
+                        color: layer.colour(),
+                        custom_size: Some(Vec2::new(width, height)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(
+                        -VIEW_HALF_WIDTH + width * (i as f32 + 0.5),
+                        y + height / 2.,
+                        layer.z,
+                    ),
+                    ..default()
+                },
+                Scroll(layer.scroll),
+                Tint(layer.colour()),
+                Tile { width, count },
+                Game,
+            ));
+        }
+    }
+}
+
+//uses the simulation's pipe speed so everything slows down together when the birds die
+fn scroll(
+    mut scrolling: Query<(&mut Transform, &Scroll)>,
+    simulation: Res<Simulation>,
+    time: Res<Time>,
+) {
+    let distance = simulation.pipe_speed() * time.delta_seconds();
+    for (mut transform, scroll) in scrolling.iter_mut() {
+        transform.translation.x -= distance * scroll.0;
+    }
+}
+
+fn wrap_strips(mut tiles: Query<(&mut Transform, &Tile)>) {
+    for (mut transform, tile) in tiles.iter_mut() {
+        if transform.translation.x + tile.width / 2. < -VIEW_HALF_WIDTH {
+            transform.translation.x += tile.width * tile.count as f32;
+        }
+    }
+}
+
+//white silhouette of a built in shape, the layer colour tints it
+fn draw(shape: &Shape, width: u32, height: u32) -> Image {
+    let profile = profile(shape, width, height as f32);
+    let mut data = Vec::with_capacity((width * height * 4) as usize);
+    for row in 0..height {
+        let from_bottom = (height - row) as f32;
+        for column in 0..width {
+            //a pixel of soft edge so the hills aren't jagged
+            let coverage = (profile[column as usize] - from_bottom + 1.).clamp(0., 1.);
+            data.extend_from_slice(&[255, 255, 255, (coverage * 255.) as u8]);
+        }
+    }
+    Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+//how tall the shape is at every column, the ends always line up so tiles join without a seam
+fn profile(shape: &Shape, width: u32, height: f32) -> Vec<f32> {
+    //same scenery every time
+    let mut rng = StdRng::seed_from_u64(0);
+    let along = |column: u32| column as f32 / width as f32;
+    match shape {
+        //whole numbers of waves across the tile so both ends match
+        Shape::Hills => (0..width)
+            .map(|column| {
+                let x = along(column) * TAU;
+                height
+                    * (0.6
+                        + 0.2 * (x + 1.3).sin()
+                        + 0.12 * (3. * x + 0.4).sin()
+                        + 0.06 * (7. * x + 2.1).sin())
+            })
+            .collect(),
+        //blocks of random widths and heights, the last one just gets cut short
+        Shape::Skyline => {
+            let mut profile = Vec::with_capacity(width as usize);
+            while profile.len() < width as usize {
+                let building = rng.gen_range(40..120);
+                let top = height * rng.gen_range(0.35..1.);
+                profile.extend(std::iter::repeat_n(top, building));
+            }
+            profile.truncate(width as usize);
+            profile
+        }
+        //round clumps, measured around the wrap so a clump can hang off one end onto the other
+        Shape::Bushes => {
+            let clumps: Vec<(f32, f32)> = (0..width / 50)
+                .map(|_| {
+                    (
+                        rng.gen_range(0.0..width as f32),
+                        height * rng.gen_range(0.4..0.7),
+                    )
+                })
+                .collect();
+            (0..width)
+                .map(|column| {
+                    clumps
+                        .iter()
+                        .map(|&(centre, radius)| {
+                            let apart = (column as f32 - centre).abs();
+                            let apart = apart.min(width as f32 - apart);
+                            (radius * radius - apart * apart).max(0.).sqrt() + height * 0.3
+                        })
+                        .fold(0., f32::max)
+                        .min(height)
+                })
+                .collect()
+        }
+        Shape::Image(_) => vec![height; width as usize],
+    }
+}
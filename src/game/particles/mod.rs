@@ -0,0 +1,291 @@
+use crate::game::{Game, Simulation, SimulationEvent};
+use crate::AppState;
+use bevy::prelude::*;
+use flappybird::sim::{DeathCause, Event};
+use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+
+//little bursts of feathers, bits of pipe, sparkles and dust when things happen in the simulation
+//everything runs off virtual time so it speeds up, slows down and pauses with the rest of the game
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Emitters>().add_systems(
+            Update,
+            (emit, update_particles)
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+//past this many new bursts are skipped, so a big multiplayer pileup can't grind the game down
+const MAX_PARTICLES: usize = 1500;
+
+//what comes out for each kind of event, a theme can swap in its own
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Emitters {
+    //every flap
+    pub feathers: Emitter,
+    //hitting a pipe
+    pub debris: Emitter,
+    //getting through a gap
+    pub sparkles: Emitter,
+    //hitting the ground
+    pub dust: Emitter,
+}
+
+//one burst of particles, every (min, max) pair is picked from at random for each particle
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Emitter {
+    pub count: u32,
+    //degrees, 0 is right and 90 is up
+    pub direction: f32,
+    //degrees either side of direction
+    pub spread: f32,
+    pub speed: (f32, f32),
+    //seconds
+    pub lifetime: (f32, f32),
+    pub size: (f32, f32),
+    //size at the end of its life compared to the start
+    pub end_size: f32,
+    //rgba from 0 to 1, fades from one to the other
+    pub colour: [f32; 4],
+    pub end_colour: [f32; 4],
+    //pulls down, negative floats up
+    pub gravity: f32,
+    //fraction of the speed lost every second
+    pub drag: f32,
+    //most degrees a second it can spin either way
+    pub spin: f32,
+    //moves along with the pipes instead of staying where it was made
+    pub scroll: bool,
+}
+
+impl Default for Emitter {
+    fn default() -> Self {
+        Emitter {
+            count: 10,
+            direction: 90.,
+            spread: 180.,
+            speed: (50., 150.),
+            lifetime: (0.5, 1.),
+            size: (4., 8.),
+            end_size: 1.,
+            colour: [1., 1., 1., 1.],
+            end_colour: [1., 1., 1., 0.],
+            gravity: 0.,
+            drag: 0.,
+            spin: 0.,
+            scroll: false,
+        }
+    }
+}
+
+impl Default for Emitters {
+    fn default() -> Self {
+        Emitters {
+            //fall out behind the bird as it goes up
+            feathers: Emitter {
+                count: 4,
+                direction: 210.,
+                spread: 35.,
+                speed: (60., 160.),
+                lifetime: (0.5, 0.9),
+                size: (5., 9.),
+                end_size: 0.6,
+                colour: [1., 0.9, 0.45, 1.],
+                end_colour: [1., 0.9, 0.45, 0.],
+                gravity: 300.,
+                drag: 1.5,
+                spin: 360.,
+                ..default()
+            },
+            debris: Emitter {
+                count: 14,
+                direction: 180.,
+                spread: 70.,
+                speed: (150., 400.),
+                lifetime: (0.6, 1.2),
+                size: (4., 9.),
+                colour: [0.35, 0.75, 0.2, 1.],
+                end_colour: [0.25, 0.55, 0.15, 0.],
+                gravity: 900.,
+                drag: 0.5,
+                spin: 720.,
+                scroll: true,
+                ..default()
+            },
+            sparkles: Emitter {
+                count: 16,
+                speed: (100., 250.),
+                lifetime: (0.3, 0.7),
+                size: (3., 6.),
+                end_size: 0.2,
+                colour: [1., 1., 0.7, 1.],
+                end_colour: [1., 0.85, 0.2, 0.],
+                drag: 2.,
+                spin: 180.,
+                ..default()
+            },
+            dust: Emitter {
+                count: 20,
+                spread: 70.,
+                speed: (40., 160.),
+                lifetime: (0.5, 1.),
+                size: (6., 14.),
+                end_size: 2.,
+                colour: [0.85, 0.75, 0.55, 0.8],
+                end_colour: [0.85, 0.75, 0.55, 0.],
+                gravity: 100.,
+                drag: 2.,
+                scroll: true,
+                ..default()
+            },
+        }
+    }
+}
+
+#[derive(Component)]
+struct Particle {
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+    size: f32,
+    end_size: f32,
+    colour: Color,
+    end_colour: Color,
+    gravity: f32,
+    drag: f32,
+    //radians a second
+    spin: f32,
+    scroll: bool,
+}
+
+fn colour(rgba: [f32; 4]) -> Color {
+    Color::rgba(rgba[0], rgba[1], rgba[2], rgba[3])
+}
+
+fn pick(rng: &mut impl Rng, (min, max): (f32, f32)) -> f32 {
+    if max > min {
+        rng.gen_range(min..max)
+    } else {
+        min
+    }
+}
+
+fn burst(commands: &mut Commands, emitter: &Emitter, at: Vec2) {
+    let mut rng = thread_rng();
+    for _ in 0..emitter.count {
+        let angle =
+            (emitter.direction + pick(&mut rng, (-emitter.spread, emitter.spread))).to_radians();
+        let size = pick(&mut rng, emitter.size);
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: colour(emitter.colour),
+                    custom_size: Some(Vec2::splat(size)),
+                    ..default()
+                },
+                //just in front of the pipes and birds
+                transform: Transform::from_translation(at.extend(1.)),
+                ..default()
+            },
+            Particle {
+                velocity: Vec2::from_angle(angle) * pick(&mut rng, emitter.speed),
+                age: 0.,
+                lifetime: pick(&mut rng, emitter.lifetime).max(0.01),
+                size,
+                end_size: emitter.end_size,
+                colour: colour(emitter.colour),
+                end_colour: colour(emitter.end_colour),
+                gravity: emitter.gravity,
+                drag: emitter.drag,
+                spin: pick(&mut rng, (-emitter.spin, emitter.spin)).to_radians(),
+                scroll: emitter.scroll,
+            },
+            Game,
+        ));
+    }
+}
+
+fn emit(
+    mut commands: Commands,
+    mut events: EventReader<SimulationEvent>,
+    simulation: Res<Simulation>,
+    emitters: Res<Emitters>,
+    particles: Query<(), With<Particle>>,
+) {
+    let mut room = MAX_PARTICLES.saturating_sub(particles.iter().len());
+    let rules = simulation.rules;
+    for event in events.read() {
+        let (emitter, bird) = match **event {
+            Event::Flapped { bird } => (&emitters.feathers, bird),
+            Event::Scored { bird } => (&emitters.sparkles, bird),
+            Event::Died {
+                bird,
+                cause: DeathCause::Pipe,
+            } => (&emitters.debris, bird),
+            Event::Died {
+                bird,
+                cause: DeathCause::Ground,
+            } => (&emitters.dust, bird),
+            _ => continue,
+        };
+        let Some(y) = simulation.birds().get(bird).map(|bird| bird.y) else {
+            continue;
+        };
+        if emitter.count as usize > room {
+            continue;
+        }
+        room -= emitter.count as usize;
+        //dust comes up off the ground under the bird
+        let y = match **event {
+            Event::Died {
+                cause: DeathCause::Ground,
+                ..
+            } => rules.floor,
+            _ => y,
+        };
+        burst(&mut commands, emitter, Vec2::new(rules.bird_x, y));
+    }
+}
+
+fn update_particles(
+    mut commands: Commands,
+    mut particles: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+    simulation: Res<Simulation>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_seconds();
+    let world = simulation.pipe_speed() * delta;
+    for (entity, mut particle, mut transform, mut sprite) in particles.iter_mut() {
+        particle.age += delta;
+        if particle.age >= particle.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        particle.velocity.y -= particle.gravity * delta;
+        let drag = (1. - particle.drag * delta).max(0.);
+        particle.velocity *= drag;
+        transform.translation += (particle.velocity * delta).extend(0.);
+        if particle.scroll {
+            transform.translation.x -= world;
+        }
+        transform.rotate_z(particle.spin * delta);
+
+        let life = particle.age / particle.lifetime;
+        let (from, to) = (
+            particle.colour.as_rgba_f32(),
+            particle.end_colour.as_rgba_f32(),
+        );
+        let [red, green, blue, alpha] = [0, 1, 2, 3].map(|i| from[i] + (to[i] - from[i]) * life);
+        sprite.color = Color::rgba(red, green, blue, alpha);
+        sprite.custom_size = Some(Vec2::splat(
+            particle.size * (1. + (particle.end_size - 1.) * life),
+        ));
+    }
+}
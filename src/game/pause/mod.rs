@@ -0,0 +1,131 @@
+use crate::game::{
+    bird::BirdInput,
+    input::{Action, Actions, Bindings},
+    versus::Versus,
+    Game, GameState,
+};
+use crate::{loading::GameAssets, AppState};
+use bevy::prelude::*;
+
+//stops the run on the pause action, from there it carries on or goes back to the menu
+//versus can't pause as the other player would be left waiting
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Paused>()
+            .add_systems(OnExit(AppState::Playing), reset)
+            .add_systems(
+                Update,
+                (
+                    //before the birds read their input so the press that unpauses can be dropped
+                    pause
+                        .before(BirdInput)
+                        .run_if(in_state(GameState::Playing))
+                        .run_if(not(resource_exists::<Versus>)),
+                    show_paused.run_if(resource_changed::<Paused>),
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Paused {
+    pub on: bool,
+    //true for the frame it carries on, confirm and flap are often the same button
+    //and that press shouldn't flap too
+    pub resumed: bool,
+}
+
+#[derive(Component)]
+struct PausedText;
+
+//stops virtual time so the simulation and everything drawn off it stop
+fn pause(
+    actions: Res<Actions>,
+    mut paused: ResMut<Paused>,
+    mut time: ResMut<Time<Virtual>>,
+    mut state: ResMut<NextState<AppState>>,
+) {
+    if paused.resumed {
+        paused.bypass_change_detection().resumed = false;
+    }
+    if !paused.on {
+        if actions.just_pressed(Action::Pause) {
+            paused.on = true;
+            time.pause();
+        }
+        return;
+    }
+    //back goes first so a key bound to both pause and back can still quit
+    if actions.just_pressed(Action::Back) {
+        state.set(AppState::MainMenu);
+    } else if actions.just_pressed(Action::Pause) || actions.just_pressed(Action::Confirm) {
+        paused.on = false;
+        paused.resumed = true;
+        time.unpause();
+    }
+}
+
+fn show_paused(
+    mut commands: Commands,
+    paused: Res<Paused>,
+    text: Query<Entity, With<PausedText>>,
+    bindings: Res<Bindings>,
+    assets: Res<GameAssets>,
+) {
+    if !paused.on {
+        for entity in text.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+    if !text.is_empty() {
+        return;
+    }
+    let style = |font_size: f32| TextStyle {
+        font: assets.font.clone(),
+        font_size,
+        color: Color::WHITE,
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0., 0., 0., 0.5).into(),
+                ..default()
+            },
+            PausedText,
+            Game,
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_sections([
+                    TextSection::new("Paused\n", style(120.)),
+                    TextSection::new(
+                        format!(
+                            "{} to carry on, {} to quit",
+                            bindings.name(Action::Confirm),
+                            bindings.name(Action::Back)
+                        ),
+                        style(40.),
+                    ),
+                ])
+                .with_text_justify(JustifyText::Center),
+            );
+        });
+}
+
+fn reset(mut paused: ResMut<Paused>, mut time: ResMut<Time<Virtual>>) {
+    *paused = Paused::default();
+    time.unpause();
+}
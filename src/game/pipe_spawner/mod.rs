@@ -1,144 +1,119 @@
-use crate::AppState;
+use crate::{loading::GameAssets, AppState};
 use bevy::{math::vec3, prelude::*};
-use bevy_rapier2d::prelude::*;
-use rand::{thread_rng, Rng};
+use flappybird::sim;
 
-use crate::game::GameState;
-use crate::game::{degrees_to_radians, Game};
-
-//sets spawnpos and a max height for the components to use
-const SPAWNPOS_X: f32 = 1200.;
-const MAXHEIGHT: f32 = 375.;
+use crate::game::{
+    degrees_to_radians,
+    sky::Tint,
+    theme::{self, Theme},
+    Game, Simulation, SimulationEvent,
+};
 
 pub struct PipeSpawnerPlugin;
 
 impl Plugin for PipeSpawnerPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(PipeTimer { ..default() })
-            //.add_systems(Startup, setup)
-            .add_systems(
-                Update,
-                spawn_pipe
-                    .run_if(in_state(GameState::Playing))
-                    .run_if(in_state(AppState::Playing)),
-            )
-            .add_systems(
-                Update,
-                pipe_slow_down
-                    .run_if(in_state(GameState::Dead))
-                    .run_if(in_state(AppState::Playing)),
-            );
+        app.add_systems(
+            Update,
+            (spawn_pipe, move_pipes)
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
     }
 }
 
-#[derive(Bundle)]
-struct PipeBundle {
-    pub rigidbody: RigidBody,
-    pub collider: Collider,
-    pub velocity: Velocity,
-    pub pipe: PipeGap,
-    pub game: Game,
-}
-
+//the pipe timer, gap heights and speeds all live in the simulation now
+//this only puts sprites where the simulation says the pipes are
 #[derive(Component)]
 pub struct PipeGap {
-    pub interacted: bool,
+    pub id: u32,
 }
 
 #[derive(Component)]
 pub struct Pipe;
 
-//pipe default to attempt to keep code looking cleaner... i failed xD
-impl Default for PipeBundle {
-    fn default() -> Self {
-        PipeBundle {
-            rigidbody: RigidBody::KinematicVelocityBased,
-            collider: Collider::cuboid(0.01, 150.),
-            velocity: Velocity {
-                linvel: Vec2::new(-200., 0.),
-                angvel: 0.,
-            },
-            pipe: PipeGap { interacted: false },
-            game: Game,
-        }
-    }
-}
-
-//timer to time the pipes spawn
-#[derive(Resource)]
-struct PipeTimer {
-    time_since_last_spawn: f32,
-    time_needed_to_spawn: f32,
-}
-
-impl Default for PipeTimer {
-    fn default() -> Self {
-        PipeTimer {
-            time_since_last_spawn: 3.,
-            time_needed_to_spawn: 2.5,
-        }
-    }
-}
-
-//creates the pipes when timer is correct
+//creates the pipes when the simulation spawns them and removes them when it does
 //god this code is a mess
 fn spawn_pipe(
-    mut pipe_timer: ResMut<PipeTimer>,
-    time: Res<Time>,
+    mut events: EventReader<SimulationEvent>,
+    simulation: Res<Simulation>,
+    pipes: Query<(Entity, &PipeGap)>,
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
+    assets: Res<GameAssets>,
+    theme: Res<Theme>,
 ) {
-    if pipe_timer.time_since_last_spawn > pipe_timer.time_needed_to_spawn {
-        pipe_timer.time_since_last_spawn = 0.;
-        let height: f32 = thread_rng().gen_range(-MAXHEIGHT..MAXHEIGHT);
-        let pipe_centre = commands
-            .spawn(PipeBundle { ..default() })
-            .insert(TransformBundle::from(Transform::from_xyz(
-                SPAWNPOS_X, height, 0.,
-            )))
-            .insert(Sensor)
-            .id();
-        let pipe1 = commands
-            .spawn(SpriteBundle {
-                texture: asset_server.load("Sprites/pipe.png"),
-                ..default()
-            })
-            .insert(Transform {
-                translation: vec3(0., 588., 0.),
-                scale: vec3(0.4, 0.4, 1.),
-                ..default()
-            })
-            .insert(Collider::cuboid(230., 1090.))
-            .insert(Pipe)
-            .id();
-        let pipe2 = commands
-            .spawn(SpriteBundle {
-                texture: asset_server.load("Sprites/pipe.png"),
-                ..default()
-            })
-            .insert(Transform::from_matrix(
-                Mat4::from_scale_rotation_translation(
-                    vec3(0.4, 0.4, 1.),
-                    Quat::from_rotation_z(degrees_to_radians(180.)),
-                    vec3(0., -588., 0.),
-                ),
-            ))
-            .insert(Collider::cuboid(230., 1090.))
-            .insert(Pipe)
-            .id();
-        commands.entity(pipe_centre).add_child(pipe1);
-        commands.entity(pipe_centre).add_child(pipe2);
+    let look = &theme.pipe;
+    //sized so the picture's collider lines up with the simulation's pipe
+    let scale = look.scale(
+        simulation.rules.pipe_half_width,
+        simulation.rules.pipe_half_height,
+    );
+    let sprite = Sprite {
+        anchor: theme::anchor(look.anchor),
+        ..default()
+    };
+    let tint = Tint(theme::colour(look.colour));
+    for event in events.read() {
+        match **event {
+            sim::Event::PipeSpawned { id } => {
+                let Some(pipe) = simulation.pipe(id) else {
+                    continue;
+                };
+                let offset = simulation.rules.gap_half_height + simulation.rules.pipe_half_height;
+                let pipe_centre = commands
+                    .spawn((PipeGap { id }, Game))
+                    .insert(SpatialBundle::from_transform(Transform::from_xyz(
+                        pipe.x, pipe.gap_y, 0.,
+                    )))
+                    .id();
+                let pipe1 = commands
+                    .spawn(SpriteBundle {
+                        texture: assets.pipe.clone(),
+                        sprite: sprite.clone(),
+                        ..default()
+                    })
+                    .insert(Transform {
+                        translation: vec3(0., offset, 0.),
+                        scale,
+                        ..default()
+                    })
+                    .insert((Pipe, tint.clone()))
+                    .id();
+                let pipe2 = commands
+                    .spawn(SpriteBundle {
+                        texture: assets.pipe.clone(),
+                        sprite: sprite.clone(),
+                        ..default()
+                    })
+                    .insert(Transform::from_matrix(
+                        Mat4::from_scale_rotation_translation(
+                            scale,
+                            Quat::from_rotation_z(degrees_to_radians(180.)),
+                            vec3(0., -offset, 0.),
+                        ),
+                    ))
+                    .insert((Pipe, tint.clone()))
+                    .id();
+                commands.entity(pipe_centre).add_child(pipe1);
+                commands.entity(pipe_centre).add_child(pipe2);
+            }
+            sim::Event::PipeRemoved { id } => {
+                for (entity, pipe_gap) in pipes.iter() {
+                    if pipe_gap.id == id {
+                        commands.entity(entity).despawn_recursive();
+                    }
+                }
+            }
+            _ => (),
+        }
     }
-    pipe_timer.time_since_last_spawn += time.delta_seconds()
 }
 
-//Slows down the pipes when the player dies
-fn pipe_slow_down(mut pipes: Query<&mut Velocity, With<PipeGap>>) {
-    for mut velocity in pipes.iter_mut() {
-        if velocity.linvel.x < 0. {
-            velocity.linvel.x += 3.;
-        } else {
-            velocity.linvel.x = 0.;
+//moves the pipes to where the simulation has them, this includes slowing down when the player dies
+fn move_pipes(mut pipes: Query<(&mut Transform, &PipeGap)>, simulation: Res<Simulation>) {
+    for (mut transform, pipe_gap) in pipes.iter_mut() {
+        if let Some(pipe) = simulation.pipe(pipe_gap.id) {
+            transform.translation.x = pipe.x;
         }
     }
 }
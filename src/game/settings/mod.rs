@@ -0,0 +1,102 @@
+use bevy::{
+    prelude::*,
+    window::{PresentMode, PrimaryWindow, WindowResized, WindowResolution},
+};
+use flappybird::{
+    config::{Config, WindowMode},
+    storage,
+};
+use std::{fs, io};
+
+//loads the player's options at startup and writes them back whenever they change
+//the window is kept matching them so changes show up straight away
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let path = storage::config_path();
+        match Config::load(&path) {
+            Ok(config) => {
+                app.insert_resource(Settings(config));
+            }
+            Err(err) => {
+                warn!("couldn't load config, using the defaults: {}", err);
+                //keeps the broken file for the player to fix instead of writing the defaults over it
+                if err.kind() == io::ErrorKind::InvalidData {
+                    let backup = path.with_extension("json.bak");
+                    match fs::rename(&path, &backup) {
+                        Ok(()) => warn!("moved the broken config to {}", backup.display()),
+                        Err(err) => warn!("couldn't move the broken config: {}", err),
+                    }
+                }
+                app.insert_resource(Settings(Config::default()))
+                    .insert_resource(LoadFailed);
+            }
+        }
+        app.add_systems(
+            Update,
+            (save, apply_display).run_if(resource_changed::<Settings>),
+        )
+        .add_systems(Update, scale_ui.run_if(on_event::<WindowResized>()));
+    }
+}
+
+//everything was laid out for this size, the camera and ui scale to fit other sizes
+pub const VIEW_WIDTH: f32 = 1920.;
+pub const VIEW_HEIGHT: f32 = 1080.;
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct Settings(pub Config);
+
+//the config file couldn't be read, nothing's written until the player changes something
+#[derive(Resource)]
+struct LoadFailed;
+
+//also runs on the first frame so there's always a file there to edit
+fn save(settings: Res<Settings>, load_failed: Option<Res<LoadFailed>>) {
+    if settings.is_added() && load_failed.is_some() {
+        return;
+    }
+    if let Err(err) = settings.save(storage::config_path()) {
+        warn!("couldn't save config: {}", err);
+    }
+}
+
+//only touches what's different so the window doesn't get rebuilt for a volume change
+fn apply_display(settings: Res<Settings>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+    let display = settings.display;
+    let mode = match display.mode {
+        WindowMode::Windowed => bevy::window::WindowMode::Windowed,
+        WindowMode::Borderless => bevy::window::WindowMode::BorderlessFullscreen,
+        WindowMode::Fullscreen => bevy::window::WindowMode::Fullscreen,
+    };
+    let present_mode = if display.vsync {
+        PresentMode::AutoVsync
+    } else {
+        PresentMode::AutoNoVsync
+    };
+    let [width, height] = display.resolution.map(|size| size.max(1) as f32);
+    for mut window in windows.iter_mut() {
+        if window.mode != mode {
+            window.mode = mode;
+        }
+        if window.present_mode != present_mode {
+            window.present_mode = present_mode;
+        }
+        if mode == bevy::window::WindowMode::Windowed
+            && (window.width() != width || window.height() != height)
+        {
+            window.resolution = WindowResolution::new(width, height);
+        }
+    }
+}
+
+//keeps the menus the same size compared to the window whatever its resolution
+fn scale_ui(windows: Query<&Window, With<PrimaryWindow>>, mut ui_scale: ResMut<UiScale>) {
+    for window in windows.iter() {
+        let scale = (window.width() / VIEW_WIDTH).min(window.height() / VIEW_HEIGHT);
+        if scale > 0. && ui_scale.0 != scale {
+            ui_scale.0 = scale;
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::game::{Game, Simulation};
+use crate::AppState;
+use bevy::{math::vec3, prelude::*};
+use flappybird::sim::TICK_RATE;
+use rand::{thread_rng, Rng};
+
+//the sky goes round dawn, day, dusk and night as a run goes on,
+//everything with a Tint gets lit to match and the stars come out at night
+pub struct SkyPlugin;
+
+impl Plugin for SkyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Playing), spawn_stars)
+            .add_systems(Update, cycle.run_if(in_state(AppState::Playing)));
+    }
+}
+
+//seconds for a whole day
+const DAY_LENGTH: f32 = 150.;
+//runs start in the morning so they look like they always have
+const START: f32 = 0.12;
+
+//colour the sky is and what everything else is lit with at points through the day, 0 is dawn
+//the last one loops back round to the first
+const TIMES: [(f32, [f32; 3], [f32; 3]); 8] = [
+    (0.0, [0.98, 0.67, 0.55], [1., 0.85, 0.8]),
+    (0.12, [0.53, 0.81, 0.92], [1., 1., 1.]),
+    (0.5, [0.53, 0.81, 0.92], [1., 1., 1.]),
+    (0.6, [0.95, 0.55, 0.38], [1., 0.78, 0.65]),
+    (0.68, [0.3, 0.22, 0.42], [0.65, 0.58, 0.75]),
+    (0.76, [0.04, 0.06, 0.17], [0.42, 0.47, 0.65]),
+    (0.92, [0.04, 0.06, 0.17], [0.42, 0.47, 0.65]),
+    (1.0, [0.98, 0.67, 0.55], [1., 0.85, 0.8]),
+];
+
+const STARS: usize = 120;
+
+//the colour something is in full daylight, it's multiplied by the light at the time of day
+#[derive(Component, Clone)]
+pub struct Tint(pub Color);
+
+#[derive(Component)]
+struct Star {
+    //so they don't all come out at once
+    shows_at: f32,
+}
+
+//how far through the day it is, from the simulation so replays get the same sky
+fn time_of_day(simulation: &Simulation) -> f32 {
+    (START + simulation.tick() as f32 / TICK_RATE as f32 / DAY_LENGTH).fract()
+}
+
+fn mix(from: [f32; 3], to: [f32; 3], amount: f32) -> [f32; 3] {
+    [0, 1, 2].map(|i| from[i] + (to[i] - from[i]) * amount)
+}
+
+//sky colour and light for a time of day, eased between the nearest two times
+fn light(time: f32) -> ([f32; 3], [f32; 3]) {
+    let next = TIMES
+        .iter()
+        .position(|&(at, ..)| at > time)
+        .unwrap_or(TIMES.len() - 1);
+    let (from, to) = (TIMES[next - 1], TIMES[next]);
+    let amount = ((time - from.0) / (to.0 - from.0)).clamp(0., 1.);
+    let amount = amount * amount * (3. - 2. * amount);
+    (mix(from.1, to.1, amount), mix(from.2, to.2, amount))
+}
+
+//0 in the day up to 1 in the middle of the night
+fn darkness(time: f32) -> f32 {
+    let (sky, _) = light(time);
+    let (day, night) = (TIMES[1].1, TIMES[5].1);
+    ((day[2] - sky[2]) / (day[2] - night[2])).clamp(0., 1.)
+}
+
+fn spawn_stars(mut commands: Commands) {
+    let mut rng = thread_rng();
+    for _ in 0..STARS {
+        let size = rng.gen_range(1.5..4.);
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::NONE,
+                    custom_size: Some(Vec2::splat(size)),
+                    ..default()
+                },
+                transform: Transform::from_translation(vec3(
+                    rng.gen_range(-960.0..960.),
+                    rng.gen_range(-150.0..540.),
+                    -50.,
+                )),
+                ..default()
+            },
+            Star {
+                shows_at: rng.gen_range(0.3..0.9),
+            },
+            Game,
+        ));
+    }
+}
+
+fn cycle(
+    simulation: Res<Simulation>,
+    mut sky: ResMut<ClearColor>,
+    mut tinted: Query<(&mut Sprite, &Tint), Without<Star>>,
+    mut stars: Query<(&mut Sprite, &Star)>,
+) {
+    let time = time_of_day(&simulation);
+    let (sky_colour, [red, green, blue]) = light(time);
+    sky.0 = Color::rgb(sky_colour[0], sky_colour[1], sky_colour[2]);
+    for (mut sprite, tint) in tinted.iter_mut() {
+        sprite.color = Color::rgba(
+            tint.0.r() * red,
+            tint.0.g() * green,
+            tint.0.b() * blue,
+            tint.0.a(),
+        );
+    }
+    let darkness = darkness(time);
+    for (mut sprite, star) in stars.iter_mut() {
+        let alpha = ((darkness - star.shows_at) / 0.1).clamp(0., 1.);
+        sprite.color = Color::rgba(1., 1., 0.9, alpha);
+    }
+}
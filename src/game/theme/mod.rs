@@ -0,0 +1,295 @@
+use crate::game::{
+    embedded,
+    parallax::{LayerKind, ParallaxLayers, Shape},
+    particles::Emitters,
+    settings::Settings,
+};
+use bevy::{prelude::*, sprite::Anchor};
+use flappybird::sim::hitbox::Hull;
+use serde::{Deserialize, Serialize};
+
+//how the game looks, read from assets/themes/<folder>/theme.json
+//anything a manifest leaves out (or points at a file that isn't there) comes from the classic theme
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Themes(discover()))
+            .add_systems(PreStartup, load_theme)
+            .add_systems(Update, load_theme.run_if(resource_changed::<Settings>));
+    }
+}
+
+const THEMES: &str = "themes";
+const MANIFEST: &str = "theme.json";
+//the folder of the theme used when none is picked
+pub const CLASSIC: &str = "classic";
+
+//every theme found in the themes folder, classic first
+#[derive(Resource, Deref)]
+pub struct Themes(pub Vec<ThemeEntry>);
+
+#[derive(Clone, Debug)]
+pub struct ThemeEntry {
+    //the folder it's in, this is what's saved in the config
+    pub folder: String,
+    pub name: String,
+}
+
+impl Themes {
+    //the one after this folder, back round to the start at the end
+    pub fn after(&self, folder: &str) -> Option<&ThemeEntry> {
+        let index = self.iter().position(|theme| theme.folder == folder);
+        self.get(index.map_or(0, |index| (index + 1) % self.len()))
+    }
+
+    pub fn name(&self, folder: &str) -> String {
+        self.iter()
+            .find(|theme| theme.folder == folder)
+            .map_or(folder.to_string(), |theme| theme.name.clone())
+    }
+}
+
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub name: String,
+    pub font: String,
+    //behind the main menu
+    pub menu_colour: [f32; 4],
+    pub bird: BirdLook,
+    pub pipe: PipeLook,
+    pub layers: ParallaxLayers,
+    pub particles: Emitters,
+    #[serde(skip)]
+    pub folder: String,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            name: "Classic".to_string(),
+            font: "fonts/blocky.ttf".to_string(),
+            menu_colour: [0.5, 0.5, 0.5, 1.],
+            bird: BirdLook::default(),
+            pipe: PipeLook::default(),
+            layers: ParallaxLayers::default(),
+            particles: Emitters::default(),
+            folder: CLASSIC.to_string(),
+        }
+    }
+}
+
+//sizes are in the picture's own pixels, the game scales the picture so the collider ends up
+//the size of the simulation's hitbox, that way every theme plays exactly the same
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BirdLook {
+    pub image: String,
+    //size it's drawn at
+    pub size: [f32; 2],
+    //where the middle of the hitbox is, -0.5 to 0.5 across the picture
+    pub anchor: [f32; 2],
+    pub colour: [f32; 4],
+    pub collider_radius: f32,
+    //outline from the hitbox tool for traced hitboxes, worked out from the picture if it's left out
+    pub hull: Option<Hull>,
+}
+
+impl Default for BirdLook {
+    fn default() -> Self {
+        BirdLook {
+            image: "Sprites/BirdBody.png".to_string(),
+            size: [60., 70.],
+            anchor: [-0.02, -0.08],
+            colour: [1., 1., 1., 1.],
+            collider_radius: 25.,
+            hull: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PipeLook {
+    //the top pipe hanging down, the bottom one is this turned upside down
+    pub image: String,
+    pub anchor: [f32; 2],
+    pub colour: [f32; 4],
+    //half the width and height of the solid part
+    pub collider: [f32; 2],
+    pub hull: Option<Hull>,
+}
+
+impl Default for PipeLook {
+    fn default() -> Self {
+        PipeLook {
+            image: "Sprites/pipe.png".to_string(),
+            anchor: [0., 0.],
+            colour: [1., 1., 1., 1.],
+            collider: [230., 1090.],
+            hull: None,
+        }
+    }
+}
+
+pub fn colour([red, green, blue, alpha]: [f32; 4]) -> Color {
+    Color::rgba(red, green, blue, alpha)
+}
+
+pub fn anchor([x, y]: [f32; 2]) -> Anchor {
+    if x == 0. && y == 0. {
+        Anchor::Center
+    } else {
+        Anchor::Custom(Vec2::new(x, y))
+    }
+}
+
+impl BirdLook {
+    //how big to draw it so the collider matches a hitbox this size
+    pub fn size(&self, radius: f32) -> Vec2 {
+        Vec2::from(self.size) * radius / self.collider_radius.max(f32::EPSILON)
+    }
+}
+
+impl PipeLook {
+    pub fn scale(&self, half_width: f32, half_height: f32) -> Vec3 {
+        let [width, height] = self.collider.map(|half| half.max(f32::EPSILON));
+        Vec3::new(half_width / width, half_height / height, 1.)
+    }
+}
+
+fn manifest(folder: &str) -> String {
+    format!("{}/{}/{}", THEMES, folder, MANIFEST)
+}
+
+//a theme's name from its manifest, none if there's no readable manifest
+fn read_name(folder: &str) -> Option<String> {
+    let text = embedded::read_to_string(&manifest(folder)).ok()?;
+    let theme: Theme = serde_json::from_str(&text).ok()?;
+    Some(theme.name)
+}
+
+fn discover() -> Vec<ThemeEntry> {
+    let mut folders: Vec<String> = embedded::folders(THEMES)
+        .into_iter()
+        .filter(|folder| embedded::exists(&manifest(folder)))
+        .collect();
+    folders.sort_by_key(|folder| (folder != CLASSIC, folder.clone()));
+    let mut themes: Vec<ThemeEntry> = folders
+        .into_iter()
+        .filter_map(|folder| {
+            read_name(&folder).map(|name| ThemeEntry {
+                folder: folder.clone(),
+                name,
+            })
+        })
+        .collect();
+    //classic is built in so it's always there even without its folder
+    if !themes.iter().any(|theme| theme.folder == CLASSIC) {
+        themes.insert(
+            0,
+            ThemeEntry {
+                folder: CLASSIC.to_string(),
+                name: Theme::default().name,
+            },
+        );
+    }
+    themes
+}
+
+//a path from a manifest as one the asset server understands, looked for in the theme's
+//folder first then the assets folder
+fn find(folder: &str, path: &str) -> Option<String> {
+    let in_theme = format!("{}/{}/{}", THEMES, folder, path);
+    if embedded::exists(&in_theme) {
+        return Some(in_theme);
+    }
+    embedded::exists(path).then(|| path.to_string())
+}
+
+//the classic file if the theme's isn't there
+fn resolve(folder: &str, path: &str, classic: &str) -> String {
+    find(folder, path).unwrap_or_else(|| {
+        warn!(
+            "theme {} is missing {}, using the classic one",
+            folder, path
+        );
+        classic.to_string()
+    })
+}
+
+impl Theme {
+    pub fn load(folder: &str) -> Result<Self, String> {
+        let path = manifest(folder);
+        let mut theme = match embedded::read_to_string(&path) {
+            Ok(text) => {
+                serde_json::from_str::<Theme>(&text).map_err(|err| format!("{}: {}", path, err))?
+            }
+            //classic doesn't need a manifest
+            Err(_) if folder == CLASSIC => Theme::default(),
+            Err(err) => return Err(format!("{}: {}", path, err)),
+        };
+        theme.folder = folder.to_string();
+
+        let classic = Theme::default();
+        theme.font = resolve(folder, &theme.font, &classic.font);
+        theme.bird.image = resolve(folder, &theme.bird.image, &classic.bird.image);
+        theme.pipe.image = resolve(folder, &theme.pipe.image, &classic.pipe.image);
+        for layer in theme.layers.iter_mut() {
+            match &mut layer.kind {
+                LayerKind::Clouds { image, .. } => {
+                    *image = resolve(folder, image, "Sprites/cloud.png")
+                }
+                //there's no classic picture for a strip so it gets drawn as hills instead
+                LayerKind::Strip { shape, .. } => {
+                    if let Shape::Image(image) = shape {
+                        *shape = match find(folder, image) {
+                            Some(image) => Shape::Image(image),
+                            None => {
+                                warn!("theme {} is missing {}, drawing hills", folder, image);
+                                Shape::Hills
+                            }
+                        };
+                    }
+                }
+            }
+        }
+        Ok(theme)
+    }
+}
+
+//folder of the theme picked in the settings
+pub fn selected(settings: &Settings) -> &str {
+    if settings.theme.is_empty() {
+        CLASSIC
+    } else {
+        &settings.theme
+    }
+}
+
+//switches to the theme in the settings, at startup and whenever it's changed
+fn load_theme(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    current: Option<Res<Theme>>,
+    mut layers: ResMut<ParallaxLayers>,
+    mut emitters: ResMut<Emitters>,
+) {
+    let folder = selected(&settings);
+    if current.is_some_and(|current| current.folder == folder) {
+        return;
+    }
+    let theme = Theme::load(folder).unwrap_or_else(|err| {
+        warn!("couldn't load theme {}, using classic: {}", folder, err);
+        //keeps the folder so it isn't tried again every time the settings change
+        Theme {
+            folder: folder.to_string(),
+            ..default()
+        }
+    });
+    info!("using theme {}", theme.name);
+    *layers = theme.layers.clone();
+    *emitters = theme.particles.clone();
+    commands.insert_resource(theme);
+}
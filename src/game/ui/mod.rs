@@ -1,5 +1,6 @@
 use crate::{
-    game::{Game, GameState, Score},
+    game::{bird::player_colour, settings::Settings, Game, GameState, Players, Score, Simulation},
+    loading::GameAssets,
     AppState,
 };
 use bevy::prelude::*;
@@ -9,6 +10,12 @@ pub struct UiPlugin;
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(AppState::Playing), setup)
+            .add_systems(
+                OnEnter(GameState::Dead),
+                results
+                    .run_if(in_state(AppState::Playing))
+                    .run_if(|players: Res<Players>| players.0 > 1),
+            )
             .add_systems(
                 Update,
                 update_score
@@ -22,19 +29,35 @@ impl Plugin for UiPlugin {
 #[derive(Component)]
 struct GameText;
 
-//sets up the score in the top left hand corner
-fn setup(mut commands: Commands, score: Res<Score>, asset_server: Res<AssetServer>) {
-    commands.spawn((
-        TextBundle::from_section(
-            score.0.to_string(),
+//what the score text says for a player, solo games just show the number
+fn score_text(player: usize, score: u32, players: usize) -> String {
+    if players == 1 {
+        score.to_string()
+    } else {
+        format!("P{} {}  ", player + 1, score)
+    }
+}
+
+//sets up the score in the top left hand corner, one section per player in their colour
+fn setup(
+    mut commands: Commands,
+    players: Res<Players>,
+    assets: Res<GameAssets>,
+    settings: Res<Settings>,
+) {
+    let font = assets.font.clone();
+    let sections = (0..players.0).map(|player| {
+        TextSection::new(
+            score_text(player, 0, players.0),
             TextStyle {
                 font_size: 100.,
-                font: asset_server.load("fonts/blocky.ttf"),
-
-                ..default()
+                font: font.clone(),
+                color: player_colour(player, &settings),
             },
         )
-        .with_style(Style {
+    });
+    commands.spawn((
+        TextBundle::from_sections(sections).with_style(Style {
             margin: UiRect::new(
                 Val::Px(50.),
                 Val::Percent(0.),
@@ -52,6 +75,66 @@ fn setup(mut commands: Commands, score: Res<Score>, asset_server: Res<AssetServe
 //could probably move this into the function when you get a point so its not being called every frame
 fn update_score(mut text: Query<&mut Text, With<GameText>>, score: Res<Score>) {
     for mut text in text.iter_mut() {
-        text.sections[0].value = score.to_string();
+        for (player, section) in text.sections.iter_mut().enumerate() {
+            let points = score.get(player).copied().unwrap_or(0);
+            section.value = score_text(player, points, score.len());
+        }
     }
 }
+
+//ranks the players once the last bird is down
+fn results(
+    mut commands: Commands,
+    simulation: Res<Simulation>,
+    assets: Res<GameAssets>,
+    settings: Res<Settings>,
+) {
+    let font: Handle<Font> = assets.font.clone();
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            Game,
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    "Results",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 100.,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Px(30.0)),
+                    ..default()
+                }),
+            );
+            for (place, player) in simulation.standings().into_iter().enumerate() {
+                parent.spawn(TextBundle::from_section(
+                    format!(
+                        "{}  P{}  {}",
+                        place + 1,
+                        player + 1,
+                        simulation.birds()[player].score
+                    ),
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 60.,
+                        color: player_colour(player, &settings),
+                    },
+                ));
+            }
+        });
+}
@@ -0,0 +1,123 @@
+use crate::game::bird::{FlapInput, SimBird};
+use crate::game::{Game, Players, Simulation, SimulationEvent, StepSimulation};
+use crate::{loading::GameAssets, AppState};
+use bevy::prelude::*;
+use flappybird::netplay::Session;
+
+//racing another game over UDP, both sides play the same seed in lockstep
+//and the other player's bird shows up as a ghost
+pub struct VersusPlugin;
+
+impl Plugin for VersusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            lockstep
+                .in_set(StepSimulation)
+                .run_if(resource_exists::<Versus>)
+                .run_if(in_state(AppState::Playing)),
+        )
+        .add_systems(
+            Update,
+            show_desync
+                .run_if(resource_exists::<Versus>)
+                .run_if(in_state(AppState::Playing)),
+        )
+        .add_systems(
+            OnExit(AppState::Playing),
+            end_match.run_if(resource_exists::<Versus>),
+        );
+    }
+}
+
+//only exists while a versus match is on, the simulation steps through this instead of on its own
+#[derive(Resource, Deref, DerefMut)]
+pub struct Versus(pub Session);
+
+//marks the desync warning so it only gets spawned once
+#[derive(Component)]
+struct DesyncText;
+
+//swaps inputs with the other side and steps the simulation once both birds' flaps for the tick are in
+fn lockstep(
+    mut versus: ResMut<Versus>,
+    mut simulation: ResMut<Simulation>,
+    mut bird: Query<(&SimBird, &mut FlapInput)>,
+    mut events: EventWriter<SimulationEvent>,
+    mut state: ResMut<NextState<AppState>>,
+) {
+    //both birds are still in the simulation so there's no carrying on alone, the match is over
+    //leaving Playing ends it properly through end_match
+    if versus.timed_out() {
+        if state.0.is_none() {
+            warn!("lost the versus opponent, ending the match");
+            state.set(AppState::MainMenu);
+        }
+        return;
+    }
+    if let Err(err) = versus.receive() {
+        warn!("versus connection error: {}", err);
+    }
+    //our flap gets played INPUT_DELAY ticks from now, the same tick on both sides
+    let tick = simulation.tick();
+    for (sim_bird, mut flap) in bird.iter_mut() {
+        if sim_bird.0 == versus.local_bird() && versus.queue_local(tick, flap.0) {
+            flap.0 = false;
+        }
+    }
+    if let Err(err) = versus.send() {
+        warn!("versus connection error: {}", err);
+    }
+    //nothing steps until the other side's input for this tick turns up
+    if let Some(flaps) = versus.flaps(tick) {
+        for (bird, flap) in flaps.into_iter().enumerate() {
+            if flap {
+                simulation.flap(bird);
+            }
+        }
+        for event in simulation.step() {
+            events.send(SimulationEvent(*event));
+        }
+        versus.record_checksum(&simulation);
+    }
+}
+
+//puts a warning up if the two games stopped agreeing with each other
+fn show_desync(
+    mut commands: Commands,
+    versus: Res<Versus>,
+    text: Query<(), With<DesyncText>>,
+    assets: Res<GameAssets>,
+) {
+    let Some(tick) = versus.desync() else {
+        return;
+    };
+    if !text.is_empty() {
+        return;
+    }
+    error!("versus desync at tick {}", tick);
+    commands.spawn((
+        TextBundle::from_section(
+            format!("DESYNC at tick {}", tick),
+            TextStyle {
+                font_size: 40.,
+                font: assets.font.clone(),
+                color: Color::RED,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(50.),
+            top: Val::Px(20.),
+            ..default()
+        }),
+        DesyncText,
+        Game,
+    ));
+}
+
+//one match per connection, back on the menu it's a normal game again
+fn end_match(mut commands: Commands, mut players: ResMut<Players>) {
+    commands.remove_resource::<Versus>();
+    players.0 = 1;
+}
@@ -0,0 +1,178 @@
+use crate::game::{parallax::VIEW_HALF_WIDTH, Game, Simulation};
+use crate::AppState;
+use bevy::{
+    math::vec3,
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+use flappybird::sim::weather::Weather;
+use rand::{thread_rng, Rng};
+
+//draws whatever weather the simulation has going, the wind itself is done in the simulation
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Downpour>()
+            .add_systems(OnEnter(AppState::Playing), spawn_fog)
+            .add_systems(
+                Update,
+                (spawn_drifts, move_drifts, thicken_fog).run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+//most raindrops and wind streaks a second at full strength
+const RAIN_RATE: f32 = 250.;
+const STREAK_RATE: f32 = 20.;
+const RAIN_SPEED: f32 = 1100.;
+//streaks go past faster than the pipes so the wind shows up against them
+const STREAK_SPEED: f32 = 3.;
+//how thick the fog gets on its thickest side
+const FOG: f32 = 0.9;
+//fog starts this far in front of the bird so it can see just a bit ahead
+const FOG_START: f32 = -250.;
+const TOP: f32 = 540.;
+
+//how many drifts are owed, bits of one carry over between frames
+#[derive(Resource, Default)]
+struct Downpour(f32);
+
+//a raindrop or a streak of wind, just flies in a straight line until it's off screen
+#[derive(Component)]
+struct Drift(Vec2);
+
+#[derive(Component)]
+struct Fog;
+
+//how bad the weather is right now, 0 when clear or when weather is off
+pub fn intensity(simulation: &Simulation) -> f32 {
+    simulation
+        .forecast()
+        .map_or(0., |forecast| forecast.intensity())
+}
+
+fn spawn_drifts(
+    mut commands: Commands,
+    simulation: Res<Simulation>,
+    mut downpour: ResMut<Downpour>,
+    time: Res<Time>,
+) {
+    let Some(forecast) = simulation.forecast() else {
+        return;
+    };
+    let (sideways, updraft) = forecast.wind();
+    let speed = simulation.pipe_speed();
+    let (rate, size, colour, velocity) = match forecast.weather() {
+        Weather::Rain => (
+            RAIN_RATE,
+            Vec2::new(2., 24.),
+            Color::rgba(0.75, 0.8, 0.95, 0.6),
+            Vec2::new(-speed * 0.5, -RAIN_SPEED),
+        ),
+        Weather::Wind => (
+            STREAK_RATE,
+            Vec2::new(90., 2.),
+            Color::rgba(1., 1., 1., 0.5),
+            Vec2::new(-(speed + sideways) * STREAK_SPEED, updraft * 2.),
+        ),
+        _ => return,
+    };
+    //lines up the drop or streak with the way it's going
+    let angle = match forecast.weather() {
+        Weather::Rain => velocity.x.atan2(-velocity.y),
+        _ => (-velocity.y).atan2(-velocity.x),
+    };
+    downpour.0 += rate * forecast.intensity() * time.delta_seconds();
+    let mut rng = thread_rng();
+    while downpour.0 >= 1. {
+        downpour.0 -= 1.;
+        //rain comes down from above, streaks blow in from the right
+        let start = match forecast.weather() {
+            Weather::Rain => vec3(
+                rng.gen_range(-VIEW_HALF_WIDTH..VIEW_HALF_WIDTH * 1.5),
+                TOP + size.y,
+                2.,
+            ),
+            _ => vec3(VIEW_HALF_WIDTH + size.x, rng.gen_range(-TOP..TOP), 2.),
+        };
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: colour,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_translation(start)
+                    .with_rotation(Quat::from_rotation_z(angle)),
+                ..default()
+            },
+            Drift(velocity),
+            Game,
+        ));
+    }
+}
+
+fn move_drifts(
+    mut commands: Commands,
+    mut drifts: Query<(Entity, &mut Transform, &Drift)>,
+    time: Res<Time>,
+) {
+    for (entity, mut transform, drift) in drifts.iter_mut() {
+        transform.translation += (drift.0 * time.delta_seconds()).extend(0.);
+        let position = transform.translation;
+        if position.y < -TOP - 50. || position.x < -VIEW_HALF_WIDTH - 100. {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+//a band over the right of the screen that gets thicker further from the bird
+fn spawn_fog(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let width = 256;
+    let data = (0..width)
+        .flat_map(|x| {
+            let amount = x as f32 / (width - 1) as f32;
+            [255, 255, 255, (amount.sqrt() * 255.) as u8]
+        })
+        .collect();
+    let texture = images.add(Image::new(
+        Extent3d {
+            width,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    ));
+    let fog_width = VIEW_HALF_WIDTH - FOG_START;
+    commands.spawn((
+        SpriteBundle {
+            texture,
+            sprite: Sprite {
+                color: Color::NONE,
+                custom_size: Some(Vec2::new(fog_width, TOP * 2.)),
+                ..default()
+            },
+            transform: Transform::from_xyz(FOG_START + fog_width / 2., 0., 3.),
+            ..default()
+        },
+        Fog,
+        Game,
+    ));
+}
+
+fn thicken_fog(mut fog: Query<&mut Sprite, With<Fog>>, simulation: Res<Simulation>) {
+    let thickness = match simulation.forecast() {
+        Some(forecast) if forecast.weather() == Weather::Fog => forecast.intensity() * FOG,
+        _ => 0.,
+    };
+    for mut sprite in fog.iter_mut() {
+        sprite.color = Color::rgba(0.85, 0.87, 0.9, thickness);
+    }
+}
@@ -0,0 +1,320 @@
+//Client for the online leaderboard
+//Scores go up with the replay of the run attached so the server can check them.
+//If the server can't be reached the submission is kept in a queue file and sent next time
+use crate::sim::{replay::Replay, Difficulty};
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, io, path::Path, time::Duration};
+
+//used when FLAPPYBIRD_LEADERBOARD isn't set, where the leaderboard_server binary listens by default
+pub const DEFAULT_URL: &str = "http://127.0.0.1:8080";
+//a slow server shouldn't leave the menu waiting forever
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Submission {
+    pub name: String,
+    pub score: u32,
+    //the replay text, see sim::replay
+    pub replay: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub name: String,
+    pub score: u32,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    //couldn't talk to the server at all, worth trying again later
+    Unreachable(String),
+    //the server answered but said no, sending it again won't help
+    Rejected(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Unreachable(err) => write!(f, "leaderboard unreachable: {}", err),
+            Error::Rejected(err) => write!(f, "leaderboard rejected the score: {}", err),
+            Error::Io(err) => write!(f, "leaderboard queue: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ureq::Error> for Error {
+    fn from(err: ureq::Error) -> Self {
+        match err {
+            ureq::Error::Status(code, response) => Error::Rejected(format!(
+                "{} {}",
+                code,
+                response.into_string().unwrap_or_default()
+            )),
+            ureq::Error::Transport(err) => Error::Unreachable(err.to_string()),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+pub struct Client {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(url: impl Into<String>) -> Self {
+        Client {
+            url: url.into().trim_end_matches('/').to_string(),
+            agent: ureq::AgentBuilder::new().timeout(TIMEOUT).build(),
+        }
+    }
+
+    //uses FLAPPYBIRD_LEADERBOARD if it's set
+    pub fn from_env() -> Self {
+        Client::new(std::env::var("FLAPPYBIRD_LEADERBOARD").unwrap_or(DEFAULT_URL.to_string()))
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn submit(&self, submission: &Submission) -> Result<(), Error> {
+        let body = serde_json::to_string(submission).expect("submissions always serialize");
+        self.agent
+            .post(&format!("{}/scores", self.url))
+            .set("Content-Type", "application/json")
+            .send_string(&body)?;
+        Ok(())
+    }
+
+    pub fn top(&self, limit: usize) -> Result<Vec<Entry>, Error> {
+        let body = self
+            .agent
+            .get(&format!("{}/scores", self.url))
+            .query("limit", &limit.to_string())
+            .call()?
+            .into_string()
+            .map_err(|err| Error::Unreachable(err.to_string()))?;
+        serde_json::from_str(&body).map_err(|err| Error::Rejected(err.to_string()))
+    }
+
+    //sends whatever is queued plus the new submissions, anything that couldn't get through stays queued
+    //submissions the server rejects are dropped, returns how many got through
+    //only one of these should run at a time as it rewrites the queue file
+    pub fn submit_queued(
+        &self,
+        submissions: Vec<Submission>,
+        queue: &Path,
+    ) -> Result<usize, Error> {
+        let mut pending = load_queue(queue)?;
+        pending.extend(submissions);
+        let mut sent = 0;
+        let mut unreachable = None;
+        let mut remaining = Vec::new();
+        for submission in pending {
+            if unreachable.is_some() {
+                remaining.push(submission);
+                continue;
+            }
+            match self.submit(&submission) {
+                Ok(()) => sent += 1,
+                Err(Error::Rejected(_)) => (),
+                Err(err) => {
+                    unreachable = Some(err);
+                    remaining.push(submission);
+                }
+            }
+        }
+        save_queue(queue, &remaining)?;
+        match unreachable {
+            Some(err) => Err(err),
+            None => Ok(sent),
+        }
+    }
+}
+
+//a queue that can't be read is moved to .json.bak and started again
+//failing here would lose every score after it as nothing new could be queued
+fn load_queue(queue: &Path) -> io::Result<Vec<Submission>> {
+    match fs::read_to_string(queue) {
+        Ok(text) => match serde_json::from_str(&text) {
+            Ok(pending) => Ok(pending),
+            Err(_) => {
+                fs::rename(queue, queue.with_extension("json.bak"))?;
+                Ok(Vec::new())
+            }
+        },
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+fn save_queue(queue: &Path, pending: &[Submission]) -> io::Result<()> {
+    if pending.is_empty() {
+        return match fs::remove_file(queue) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        };
+    }
+    if let Some(parent) = queue.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(
+        queue,
+        serde_json::to_string_pretty(pending).expect("submissions always serialize"),
+    )
+}
+
+//there's one board so only runs on the standard rules go on it, mutators and other difficulties
+//would be ranked against runs they aren't comparable with
+pub fn ranked(replay: &Replay) -> bool {
+    replay.difficulty == Difficulty::default() && replay.mutators.is_empty()
+}
+
+//the name scores go up under, FLAPPYBIRD_NAME or the login name
+pub fn player_name() -> String {
+    ["FLAPPYBIRD_NAME", "USER", "USERNAME"]
+        .iter()
+        .find_map(|var| {
+            std::env::var(var)
+                .ok()
+                .filter(|name| !name.trim().is_empty())
+        })
+        .unwrap_or("anonymous".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        path::PathBuf,
+        thread,
+    };
+
+    //a fresh folder in the temp dir for each test
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "flappybird-leaderboard-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn submission(name: &str) -> Submission {
+        Submission {
+            name: name.to_string(),
+            score: 1,
+            replay: String::new(),
+        }
+    }
+
+    //answers one request with each status then stops listening, so anything after is unreachable
+    fn serve(statuses: &[&'static str]) -> Client {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let statuses = statuses.to_vec();
+        thread::spawn(move || {
+            for status in statuses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{{}}",
+                    status
+                )
+                .unwrap();
+            }
+        });
+        Client::new(url)
+    }
+
+    //nothing listens on port 1
+    fn unreachable() -> Client {
+        Client::new("http://127.0.0.1:1")
+    }
+
+    #[test]
+    fn queue_round_trips() {
+        let queue = temp_dir("round_trip").join("queue.json");
+        let pending = vec![submission("a"), submission("b")];
+        save_queue(&queue, &pending).unwrap();
+        assert_eq!(load_queue(&queue).unwrap(), pending);
+        save_queue(&queue, &[]).unwrap();
+        assert!(!queue.exists());
+        assert_eq!(load_queue(&queue).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn unreachable_server_keeps_everything_queued() {
+        let queue = temp_dir("unreachable").join("queue.json");
+        save_queue(&queue, &[submission("a")]).unwrap();
+        let result = unreachable().submit_queued(vec![submission("b")], &queue);
+        assert!(matches!(result, Err(Error::Unreachable(_))));
+        assert_eq!(
+            load_queue(&queue).unwrap(),
+            vec![submission("a"), submission("b")]
+        );
+    }
+
+    #[test]
+    fn rejected_are_dropped_and_the_rest_wait_after_the_server_goes() {
+        let queue = temp_dir("rejected").join("queue.json");
+        save_queue(&queue, &[submission("a"), submission("b")]).unwrap();
+        let client = serve(&["201 Created", "422 Unprocessable Entity"]);
+        let result = client.submit_queued(vec![submission("c")], &queue);
+        assert!(matches!(result, Err(Error::Unreachable(_))));
+        assert_eq!(load_queue(&queue).unwrap(), vec![submission("c")]);
+    }
+
+    #[test]
+    fn everything_sent_empties_the_queue() {
+        let queue = temp_dir("sent").join("queue.json");
+        save_queue(&queue, &[submission("a")]).unwrap();
+        let client = serve(&["201 Created", "201 Created"]);
+        assert_eq!(
+            client.submit_queued(vec![submission("b")], &queue).unwrap(),
+            2
+        );
+        assert!(!queue.exists());
+    }
+
+    #[test]
+    fn broken_queue_is_backed_up_and_new_scores_still_queue() {
+        let dir = temp_dir("broken");
+        let queue = dir.join("queue.json");
+        fs::write(&queue, "not json").unwrap();
+        let result = unreachable().submit_queued(vec![submission("a")], &queue);
+        assert!(matches!(result, Err(Error::Unreachable(_))));
+        assert_eq!(load_queue(&queue).unwrap(), vec![submission("a")]);
+        assert_eq!(
+            fs::read_to_string(dir.join("queue.json.bak")).unwrap(),
+            "not json"
+        );
+    }
+}
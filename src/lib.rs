@@ -0,0 +1,8 @@
+pub mod challenge;
+pub mod config;
+pub mod daily;
+pub mod leaderboard;
+pub mod netplay;
+pub mod network;
+pub mod sim;
+pub mod storage;
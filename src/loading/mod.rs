@@ -0,0 +1,250 @@
+use crate::{
+    game::{
+        input::{Action, Actions, Bindings},
+        parallax::{LayerKind, Shape},
+        theme::Theme,
+        versus::Versus,
+    },
+    AppState,
+};
+use bevy::{app::AppExit, asset::LoadState, prelude::*, utils::HashMap};
+
+//loads every picture, font and sound up front so nothing pops in on the first frames of a run
+//shows how far along it is and stops on a list of anything that's missing or broken
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameAssets>()
+            .init_resource::<Preload>()
+            .add_systems(OnEnter(AppState::Loading), setup)
+            .add_systems(OnExit(AppState::Loading), exit)
+            .add_systems(
+                Update,
+                (
+                    load_theme_assets.run_if(resource_changed::<Theme>),
+                    progress.run_if(in_state(AppState::Loading)),
+                )
+                    .chain(),
+            );
+    }
+}
+
+const BAR_WIDTH: f32 = 600.;
+
+//handles for everything the theme draws with, so they're loaded before they're needed
+#[derive(Resource, Default)]
+pub struct GameAssets {
+    pub font: Handle<Font>,
+    pub bird: Handle<Image>,
+    pub pipe: Handle<Image>,
+    //pictures the parallax layers use, by their path
+    images: HashMap<String, Handle<Image>>,
+}
+
+impl GameAssets {
+    pub fn image(&self, path: &str) -> Handle<Image> {
+        self.images.get(path).cloned().unwrap_or_default()
+    }
+
+    fn handles(&self, theme: &Theme) -> Vec<(String, UntypedHandle)> {
+        let mut handles = vec![
+            (theme.font.clone(), self.font.clone().untyped()),
+            (theme.bird.image.clone(), self.bird.clone().untyped()),
+            (theme.pipe.image.clone(), self.pipe.clone().untyped()),
+        ];
+        handles.extend(
+            self.images
+                .iter()
+                .map(|(path, handle)| (path.clone(), handle.clone().untyped())),
+        );
+        handles
+    }
+}
+
+//anything else the loading screen should wait on, other plugins add to it as they load things
+#[derive(Resource, Default)]
+pub struct Preload(HashMap<String, UntypedHandle>);
+
+impl Preload {
+    pub fn add<A: Asset>(&mut self, path: &str, handle: &Handle<A>) {
+        self.0.insert(path.to_string(), handle.clone().untyped());
+    }
+}
+
+#[derive(Component)]
+struct LoadingComp;
+
+#[derive(Component)]
+struct ProgressBar;
+
+#[derive(Component)]
+struct ProgressText;
+
+//again whenever the theme is switched, the new one loads in the background on the menu
+fn load_theme_assets(
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    mut assets: ResMut<GameAssets>,
+) {
+    let images = theme
+        .layers
+        .iter()
+        .filter_map(|layer| match &layer.kind {
+            LayerKind::Clouds { image, .. } => Some(image),
+            LayerKind::Strip {
+                shape: Shape::Image(image),
+                ..
+            } => Some(image),
+            LayerKind::Strip { .. } => None,
+        })
+        .map(|path| (path.clone(), asset_server.load(path.clone())))
+        .collect();
+    *assets = GameAssets {
+        font: asset_server.load(theme.font.clone()),
+        bird: asset_server.load(theme.bird.image.clone()),
+        pipe: asset_server.load(theme.pipe.image.clone()),
+        images,
+    };
+}
+
+//uses bevy's built in font, the theme's one might be what's broken
+fn setup(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(20.),
+                    ..default()
+                },
+                ..default()
+            },
+            LoadingComp,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Loading",
+                TextStyle {
+                    font_size: 60.,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(BAR_WIDTH),
+                        height: Val::Px(24.),
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.3, 0.3, 0.3).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Px(0.),
+                                height: Val::Percent(100.),
+                                ..default()
+                            },
+                            background_color: Color::WHITE.into(),
+                            ..default()
+                        },
+                        ProgressBar,
+                    ));
+                });
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 30.,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                )
+                .with_text_justify(JustifyText::Center),
+                ProgressText,
+            ));
+        });
+}
+
+//moves on once everything's loaded, or stays on the list of what failed
+#[allow(clippy::too_many_arguments)]
+fn progress(
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    assets: Res<GameAssets>,
+    preload: Res<Preload>,
+    versus: Option<Res<Versus>>,
+    actions: Res<Actions>,
+    bindings: Res<Bindings>,
+    mut bar: Query<&mut Style, With<ProgressBar>>,
+    mut text: Query<&mut Text, With<ProgressText>>,
+    mut state: ResMut<NextState<AppState>>,
+    mut app_exit_events: EventWriter<AppExit>,
+) {
+    let handles = assets.handles(&theme);
+    let handles = handles
+        .iter()
+        .map(|(path, handle)| (path, handle))
+        .chain(preload.0.iter());
+    let (mut total, mut loaded) = (0, 0);
+    let mut failed = Vec::new();
+    for (path, handle) in handles {
+        total += 1;
+        match asset_server.get_load_state(handle) {
+            Some(LoadState::Loaded) => loaded += 1,
+            Some(LoadState::Failed) | None => failed.push(path.clone()),
+            _ => (),
+        }
+    }
+
+    if failed.is_empty() {
+        for mut style in bar.iter_mut() {
+            style.width = Val::Px(BAR_WIDTH * loaded as f32 / total.max(1) as f32);
+        }
+        for mut text in text.iter_mut() {
+            text.sections[0].value = format!("{} / {}", loaded, total);
+        }
+        if loaded < total {
+            return;
+        }
+    } else {
+        failed.sort();
+        for mut text in text.iter_mut() {
+            text.sections[0].value = format!(
+                "Couldn't load:\n{}\n\n{} to carry on without them, {} to quit",
+                failed.join("\n"),
+                bindings.name(Action::Confirm),
+                bindings.name(Action::Back)
+            );
+            text.sections[0].style.color = Color::rgb(1., 0.4, 0.4);
+        }
+        if actions.just_pressed(Action::Back) {
+            app_exit_events.send(AppExit);
+        }
+        if !actions.just_pressed(Action::Confirm) {
+            return;
+        }
+        warn!("carrying on without {}", failed.join(", "));
+    }
+
+    //a versus match goes straight into the game
+    state.set(if versus.is_some() {
+        AppState::Playing
+    } else {
+        AppState::MainMenu
+    });
+}
+
+fn exit(mut commands: Commands, query: Query<Entity, With<LoadingComp>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -2,35 +2,119 @@
 
 use bevy::{
     prelude::*,
+    render::camera::ScalingMode,
     winit::{UpdateMode, WinitSettings},
 };
 
+mod clipboard;
 mod game;
+mod loading;
 mod mainmenu;
 
-use game::GamePlugin;
+use clipboard::ClipboardPlugin;
+use flappybird::{netplay::Session, network::Network};
+use game::{
+    autopilot::Autopilot,
+    settings::{VIEW_HEIGHT, VIEW_WIDTH},
+    versus::Versus,
+    GamePlugin, NextSeed, Players,
+};
+use loading::LoadingPlugin;
 use mainmenu::MainMenuPlugin;
+use std::time::Duration;
 
 fn main() {
-    App::new()
-        .insert_resource(WinitSettings {
-            focused_mode: UpdateMode::Continuous,
-            unfocused_mode: UpdateMode::Continuous,
-        })
-        .insert_resource(ClearColor(Color::GRAY))
-        .insert_state(AppState::MainMenu)
-        .add_plugins((GamePlugin, MainMenuPlugin))
-        .add_systems(Startup, setup)
-        .run()
+    let args: Vec<String> = std::env::args().collect();
+    //--autopilot lets the bot play run after run for soak testing
+    let soak = args.iter().any(|arg| arg == "--autopilot");
+    //--genome <file> has the autopilot fly a network from the train binary
+    let network = args
+        .iter()
+        .position(|arg| arg == "--genome")
+        .and_then(|index| args.get(index + 1))
+        .and_then(|path| match Network::load(path) {
+            Ok(network) => Some(network),
+            Err(err) => {
+                eprintln!(
+                    "couldn't load genome {}: {}, using the built in bot",
+                    path, err
+                );
+                None
+            }
+        });
+    //--host <port> or --join <address:port> starts a versus match against another copy of the game
+    let versus = match versus_session(&args) {
+        Ok(versus) => versus,
+        Err(err) => {
+            eprintln!("couldn't start versus: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let mut app = App::new();
+    app.insert_resource(WinitSettings {
+        focused_mode: UpdateMode::Continuous,
+        unfocused_mode: UpdateMode::Continuous,
+    })
+    .insert_resource(ClearColor(Color::GRAY))
+    .insert_state(AppState::Loading)
+    .add_plugins((GamePlugin, LoadingPlugin, MainMenuPlugin, ClipboardPlugin))
+    .insert_resource(Autopilot {
+        enabled: soak,
+        soak,
+        network,
+    })
+    .add_systems(Startup, setup);
+    if let Some(session) = versus {
+        app.insert_resource(NextSeed(Some(session.seed())))
+            .insert_resource(Players(2))
+            .insert_resource(Versus(session));
+    }
+    app.run()
+}
+
+//how long to wait for the other player to turn up
+const VERSUS_WAIT: Duration = Duration::from_secs(120);
+
+fn versus_session(args: &[String]) -> std::io::Result<Option<Session>> {
+    let value = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|index| args.get(index + 1))
+    };
+    if let Some(port) = value("--host") {
+        let port = port.parse().map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "--host needs a port")
+        })?;
+        println!("waiting for an opponent on port {}", port);
+        return Session::host(port, rand::random(), VERSUS_WAIT).map(Some);
+    }
+    if let Some(address) = value("--join") {
+        println!("joining {}", address);
+        return Session::join(address.as_str(), VERSUS_WAIT).map(Some);
+    }
+    Ok(None)
 }
 
+//always shows the whole 1920x1080 field whatever size the window is
 fn setup(mut commands: Commands) {
-    commands.spawn(Camera2dBundle::default());
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scaling_mode: ScalingMode::AutoMin {
+                min_width: VIEW_WIDTH,
+                min_height: VIEW_HEIGHT,
+            },
+            ..Camera2dBundle::default().projection
+        },
+        ..default()
+    });
 }
 
 //App state so we can keep track of if we're on mainmenu or playing
 #[derive(States, Debug, Hash, Eq, PartialEq, Clone)]
 pub enum AppState {
+    //preloading assets, goes to the main menu (or straight into a versus match) after
+    Loading,
     MainMenu,
     Playing,
 }
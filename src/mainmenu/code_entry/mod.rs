@@ -0,0 +1,218 @@
+use super::{focus::FocusGroup, MainMenuComp};
+use crate::{
+    clipboard::Clipboard,
+    game::{
+        autopilot::Autopilot,
+        input::{Action, Actions, Bindings},
+        Mode,
+    },
+    AppState,
+};
+use bevy::{input::keyboard::KeyCode, prelude::*, ui::FocusPolicy};
+use flappybird::challenge::{Challenge, Course};
+
+//a box on the main menu to type or paste a challenge code into and play it
+pub struct CodeEntryPlugin;
+
+impl Plugin for CodeEntryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CodeEntry>()
+            .add_systems(OnExit(AppState::MainMenu), close)
+            .add_systems(
+                Update,
+                (
+                    type_code.run_if(|entry: Res<CodeEntry>| entry.open),
+                    show_panel.run_if(resource_changed::<CodeEntry>),
+                )
+                    .chain()
+                    .run_if(in_state(AppState::MainMenu)),
+            );
+    }
+}
+
+//longest code we bother keeping, real ones are about 20 characters with the dashes
+const MAX_LENGTH: usize = 40;
+
+#[derive(Resource, Default)]
+pub struct CodeEntry {
+    open: bool,
+    text: String,
+    error: Option<String>,
+}
+
+impl CodeEntry {
+    pub fn open(&mut self) {
+        *self = CodeEntry {
+            open: true,
+            ..default()
+        };
+    }
+
+    //only keeps characters that can be in a code so pasting a whole sentence mostly works
+    fn push(&mut self, text: &str) {
+        for c in text.chars() {
+            if (c.is_ascii_alphanumeric() || c == '-') && self.text.len() < MAX_LENGTH {
+                self.text.push(c.to_ascii_uppercase());
+            }
+        }
+        self.error = None;
+    }
+}
+
+#[derive(Component)]
+struct CodePanel;
+
+#[derive(Component)]
+struct CodeText;
+
+#[derive(Component)]
+struct CodeErrorText;
+
+fn close(mut entry: ResMut<CodeEntry>) {
+    entry.open = false;
+}
+
+#[allow(clippy::too_many_arguments)]
+fn type_code(
+    mut entry: ResMut<CodeEntry>,
+    mut characters: EventReader<ReceivedCharacter>,
+    keys: Res<ButtonInput<KeyCode>>,
+    actions: Res<Actions>,
+    mut clipboard: NonSendMut<Clipboard>,
+    mut mode: ResMut<Mode>,
+    mut autopilot: ResMut<Autopilot>,
+    mut state: ResMut<NextState<AppState>>,
+    panel: Query<(), With<CodePanel>>,
+) {
+    //the confirm that opened the box isn't for the box
+    if panel.is_empty() {
+        return;
+    }
+    for character in characters.read() {
+        entry.push(&character.char);
+    }
+    let ctrl = keys.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+    ]);
+    if ctrl && keys.just_pressed(KeyCode::KeyV) {
+        if let Some(pasted) = clipboard.get() {
+            entry.push(&pasted);
+        }
+    }
+    if keys.just_pressed(KeyCode::Backspace) {
+        entry.text.pop();
+        entry.error = None;
+    }
+    if actions.just_pressed(Action::Back) {
+        entry.open = false;
+    }
+    if actions.just_pressed(Action::Confirm) {
+        match entry.text.parse::<Challenge>() {
+            Ok(challenge) => {
+                info!("playing challenge {}", challenge.describe());
+                //daily codes play as the daily so they count towards that day's best
+                *mode = match challenge.course {
+                    Course::Daily(date) => Mode::Daily(date),
+                    Course::Seed(_) => Mode::Challenge(challenge),
+                };
+                autopilot.enabled = false;
+                entry.open = false;
+                state.set(AppState::Playing);
+            }
+            Err(err) => entry.error = Some(err.to_string()),
+        }
+    }
+}
+
+//opens, closes and updates the panel to match the entry
+fn show_panel(
+    mut commands: Commands,
+    entry: Res<CodeEntry>,
+    panel: Query<Entity, With<CodePanel>>,
+    mut code_text: Query<&mut Text, (With<CodeText>, Without<CodeErrorText>)>,
+    mut error_text: Query<&mut Text, (With<CodeErrorText>, Without<CodeText>)>,
+    bindings: Res<Bindings>,
+) {
+    if !entry.open {
+        for entity in panel.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+    if panel.is_empty() {
+        spawn_panel(&mut commands, &bindings);
+    }
+    for mut text in code_text.iter_mut() {
+        text.sections[0].value = format!("{}_", entry.text);
+    }
+    for mut text in error_text.iter_mut() {
+        text.sections[0].value = entry.error.clone().unwrap_or_default();
+    }
+}
+
+fn spawn_panel(commands: &mut Commands, bindings: &Bindings) {
+    let text_style = TextStyle {
+        font_size: 40.0,
+        color: Color::WHITE,
+        ..default()
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(30.0),
+                    ..default()
+                },
+                background_color: Color::rgba(0., 0., 0., 0.85).into(),
+                //stops clicks going through to the menu underneath
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            CodePanel,
+            MainMenuComp,
+            //there's nothing to move between but it keeps confirm off the menu underneath
+            FocusGroup,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Enter a challenge code",
+                TextStyle {
+                    font_size: 60.0,
+                    ..text_style.clone()
+                },
+            ));
+            parent.spawn((TextBundle::from_section("_", text_style.clone()), CodeText));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        color: Color::RED,
+                        ..text_style.clone()
+                    },
+                ),
+                CodeErrorText,
+            ));
+            parent.spawn(TextBundle::from_section(
+                format!(
+                    "Ctrl+V to paste, {} to play, {} to go back",
+                    bindings.name(Action::Confirm),
+                    bindings.name(Action::Back)
+                ),
+                TextStyle {
+                    font_size: 25.0,
+                    color: Color::GRAY,
+                    ..default()
+                },
+            ));
+        });
+}
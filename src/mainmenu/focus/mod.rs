@@ -0,0 +1,179 @@
+use super::{HOVERED_BUTTON, NORMAL_BUTTON};
+use crate::game::input::{Action, Actions, UpdateActions};
+use bevy::{prelude::*, ui::UiSystem};
+
+//moves between the buttons on a menu with up and down, confirm presses the one picked out
+//so everything works without a mouse
+//presses go through Interaction so each menu's button system handles them like a click
+pub struct FocusPlugin;
+
+impl Plugin for FocusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Focus>()
+            .add_systems(
+                PreUpdate,
+                (track_groups, navigate)
+                    .chain()
+                    .after(UiSystem::Focus)
+                    .after(UpdateActions),
+            )
+            .add_systems(PostUpdate, show_focus);
+    }
+}
+
+//the buttons under this can be moved between, the newest group takes over from the ones
+//under it until it's gone, like a panel over the menu
+#[derive(Component)]
+pub struct FocusGroup;
+
+#[derive(Resource, Default)]
+pub struct Focus {
+    //with what had the focus on them when a group went over them
+    groups: Vec<(Entity, Option<Entity>)>,
+    //the buttons in the top group from the top of the screen down
+    buttons: Vec<Entity>,
+    focused: Option<Entity>,
+    //what's drawn as focused
+    shown: Option<Entity>,
+    //pressed with confirm last frame, let go of on the next one
+    pressed: Option<Entity>,
+    //while something else wants every key, like picking a new binding
+    pub held: bool,
+}
+
+impl Focus {
+    pub fn focused(&self) -> Option<Entity> {
+        self.focused
+    }
+
+    //only buttons on the top group can take the focus
+    pub fn focus(&mut self, button: Entity) {
+        if self.buttons.contains(&button) {
+            self.focused = Some(button);
+        }
+    }
+}
+
+fn track_groups(
+    added: Query<Entity, Added<FocusGroup>>,
+    mut removed: RemovedComponents<FocusGroup>,
+    mut focus: ResMut<Focus>,
+) {
+    for entity in removed.read() {
+        if focus
+            .groups
+            .last()
+            .is_some_and(|(group, _)| *group == entity)
+        {
+            //back to where it was before the group went over
+            focus.focused = focus
+                .groups
+                .iter()
+                .rev()
+                .nth(1)
+                .and_then(|(_, saved)| *saved);
+        }
+        focus.groups.retain(|(group, _)| *group != entity);
+    }
+    for entity in added.iter() {
+        let focused = focus.focused.take();
+        if let Some((_, saved)) = focus.groups.last_mut() {
+            *saved = focused;
+        }
+        focus.groups.push((entity, None));
+    }
+}
+
+fn navigate(
+    mut focus: ResMut<Focus>,
+    actions: Res<Actions>,
+    children: Query<&Children>,
+    mut buttons: Query<(&mut Interaction, &GlobalTransform), With<Button>>,
+) {
+    if let Some(pressed) = focus.pressed.take() {
+        if let Ok((mut interaction, _)) = buttons.get_mut(pressed) {
+            if *interaction == Interaction::Pressed {
+                *interaction = Interaction::None;
+            }
+        }
+    }
+
+    let mut list: Vec<(Entity, Vec2)> = focus.groups.last().map_or(Vec::new(), |(group, _)| {
+        children
+            .iter_descendants(*group)
+            .filter_map(|entity| {
+                let (_, transform) = buttons.get(entity).ok()?;
+                Some((entity, transform.translation().truncate()))
+            })
+            .collect()
+    });
+    //ui goes y down so this is top to bottom then left to right
+    list.sort_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+    focus.buttons = list.into_iter().map(|(entity, _)| entity).collect();
+    if focus
+        .focused
+        .is_some_and(|focused| !focus.buttons.contains(&focused))
+    {
+        focus.focused = None;
+    }
+
+    //the mouse moving onto a button picks it out too so the two don't fight
+    let hovered = focus.buttons.iter().copied().find(|entity| {
+        buttons.get_mut(*entity).is_ok_and(|(interaction, _)| {
+            interaction.is_changed() && *interaction == Interaction::Hovered
+        })
+    });
+    if hovered.is_some() {
+        focus.focused = hovered;
+    }
+
+    if focus.held || focus.buttons.is_empty() {
+        return;
+    }
+    let count = focus.buttons.len();
+    let index = focus
+        .focused
+        .and_then(|focused| focus.buttons.iter().position(|entity| *entity == focused));
+    let step = |index: Option<usize>, by: usize| index.map_or(0, |index| (index + by) % count);
+    if actions.just_pressed(Action::Down) {
+        focus.focused = Some(focus.buttons[step(index, 1)]);
+    } else if actions.just_pressed(Action::Up) {
+        focus.focused = Some(focus.buttons[step(index, count - 1)]);
+    } else if actions.just_pressed(Action::Confirm) {
+        match focus.focused {
+            Some(focused) => {
+                if let Ok((mut interaction, _)) = buttons.get_mut(focused) {
+                    *interaction = Interaction::Pressed;
+                    focus.pressed = Some(focused);
+                }
+            }
+            //the first press just shows where the focus starts
+            None => focus.focused = Some(focus.buttons[0]),
+        }
+    }
+}
+
+//the picked out button looks hovered, anything the mouse is on or pressing is left to the menus
+fn show_focus(
+    mut focus: ResMut<Focus>,
+    mut buttons: Query<(&Interaction, &mut BackgroundColor, &mut BorderColor), With<Button>>,
+) {
+    let mut paint = |entity: Entity, colour: Color, border: Color| {
+        if let Ok((Interaction::None, mut background, mut border_colour)) = buttons.get_mut(entity)
+        {
+            if background.0 != colour {
+                background.0 = colour;
+            }
+            if border_colour.0 != border {
+                border_colour.0 = border;
+            }
+        }
+    };
+    if let Some(shown) = focus.shown.filter(|shown| Some(*shown) != focus.focused) {
+        paint(shown, NORMAL_BUTTON, Color::BLACK);
+    }
+    if let Some(focused) = focus.focused {
+        paint(focused, HOVERED_BUTTON, Color::WHITE);
+    }
+    focus.shown = focus.focused;
+}
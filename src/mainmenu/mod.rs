@@ -1,12 +1,47 @@
-use crate::AppState;
+use crate::{
+    game::{
+        autopilot::Autopilot,
+        daily::Daily,
+        input::{Action, Actions},
+        leaderboard::{TopScores, TOP_SCORES},
+        settings::Settings,
+        theme::{self, Theme, Themes},
+        Mode, Players, SelectedMutators, MAX_PLAYERS,
+    },
+    loading::GameAssets,
+    AppState,
+};
 use bevy::prelude::*;
+use code_entry::{CodeEntry, CodeEntryPlugin};
+use flappybird::{daily::Date, sim::Mutators};
+use focus::{Focus, FocusGroup, FocusPlugin};
+use settings_menu::{SettingsMenu, SettingsMenuPlugin};
+mod code_entry;
+mod focus;
+mod settings_menu;
 pub struct MainMenuPlugin;
 
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::MainMenu), setup)
+        app.add_plugins((CodeEntryPlugin, SettingsMenuPlugin, FocusPlugin))
+            .add_systems(OnEnter(AppState::MainMenu), setup)
             .add_systems(OnExit(AppState::MainMenu), exit)
-            .add_systems(Update, button_system.run_if(in_state(AppState::MainMenu)));
+            .add_systems(
+                Update,
+                (button_system, back_to_exit).run_if(in_state(AppState::MainMenu)),
+            )
+            .add_systems(
+                Update,
+                show_theme
+                    .run_if(resource_changed::<Theme>)
+                    .run_if(in_state(AppState::MainMenu)),
+            )
+            .add_systems(
+                Update,
+                show_top_scores
+                    .run_if(resource_changed::<TopScores>)
+                    .run_if(in_state(AppState::MainMenu)),
+            );
     }
 }
 
@@ -17,13 +52,37 @@ const PRESSED_BUTTON: Color = Color::rgb(0.5, 0.5, 0.5);
 #[derive(Component)]
 struct MainMenuComp;
 
+//text on the players button so it can show the new count when pressed
+#[derive(Component)]
+struct PlayersText;
+
+//text on the mutators button
+#[derive(Component)]
+struct MutatorsText;
+
+//text on the theme button
+#[derive(Component)]
+struct ThemeText;
+
+//text listing the online top scores
+#[derive(Component)]
+struct TopScoresText;
+
 #[derive(Component)]
 enum MenuButtonAction {
     Play,
+    Daily,
+    Code,
+    Players,
+    Mutators,
+    Theme,
+    Autopilot,
+    Settings,
     Exit,
 }
 
 //manages the button
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn button_system(
     mut interaction_query: Query<
         (
@@ -35,6 +94,26 @@ fn button_system(
         (Changed<Interaction>, With<Button>),
     >,
     mut state: ResMut<NextState<AppState>>,
+    mut autopilot: ResMut<Autopilot>,
+    mut mode: ResMut<Mode>,
+    mut players: ResMut<Players>,
+    mut players_text: Query<
+        &mut Text,
+        (With<PlayersText>, Without<MutatorsText>, Without<ThemeText>),
+    >,
+    mut mutators: ResMut<SelectedMutators>,
+    mut mutators_text: Query<
+        &mut Text,
+        (With<MutatorsText>, Without<PlayersText>, Without<ThemeText>),
+    >,
+    mut settings: ResMut<Settings>,
+    themes: Res<Themes>,
+    mut theme_text: Query<
+        &mut Text,
+        (With<ThemeText>, Without<PlayersText>, Without<MutatorsText>),
+    >,
+    mut code_entry: ResMut<CodeEntry>,
+    mut settings_menu: ResMut<SettingsMenu>,
     mut app_exit_events: ResMut<Events<bevy::app::AppExit>>,
 ) {
     for (interaction, mut color, mut border_color, menu_action) in &mut interaction_query {
@@ -44,8 +123,47 @@ fn button_system(
                 border_color.0 = Color::RED;
                 match menu_action {
                     MenuButtonAction::Play => {
+                        autopilot.enabled = false;
+                        *mode = Mode::Standard;
+                        state.set(AppState::Playing);
+                    }
+                    //today's course, the same one everybody else gets
+                    MenuButtonAction::Daily => {
+                        autopilot.enabled = false;
+                        *mode = Mode::Daily(Date::today());
                         state.set(AppState::Playing);
                     }
+                    MenuButtonAction::Code => code_entry.open(),
+                    //cycles through 1 to MAX_PLAYERS birds on the one keyboard
+                    MenuButtonAction::Players => {
+                        players.0 = players.0 % MAX_PLAYERS + 1;
+                        for mut text in players_text.iter_mut() {
+                            text.sections[0].value = players_label(players.0);
+                        }
+                    }
+                    //steps through every mix of mutators
+                    MenuButtonAction::Mutators => {
+                        let next = (mutators.bits() + 1) % (Mutators::all().bits() + 1);
+                        mutators.0 = Mutators::from_bits(next).unwrap_or_default();
+                        for mut text in mutators_text.iter_mut() {
+                            text.sections[0].value = mutators_label(mutators.0);
+                        }
+                    }
+                    //the look changes straight away and is saved for next time
+                    MenuButtonAction::Theme => {
+                        if let Some(next) = themes.after(theme::selected(&settings)) {
+                            settings.theme = next.folder.clone();
+                        }
+                        for mut text in theme_text.iter_mut() {
+                            text.sections[0].value = theme_label(&themes, &settings);
+                        }
+                    }
+                    MenuButtonAction::Autopilot => {
+                        autopilot.enabled = true;
+                        *mode = Mode::Standard;
+                        state.set(AppState::Playing);
+                    }
+                    MenuButtonAction::Settings => settings_menu.open(),
                     MenuButtonAction::Exit => {
                         app_exit_events.send(bevy::app::AppExit);
                     }
@@ -63,18 +181,107 @@ fn button_system(
     }
 }
 
+//back on the main menu itself has nowhere to go, so it picks out exit instead
+fn back_to_exit(
+    actions: Res<Actions>,
+    mut focus: ResMut<Focus>,
+    buttons: Query<(Entity, &MenuButtonAction)>,
+) {
+    if !actions.just_pressed(Action::Back) {
+        return;
+    }
+    for (entity, action) in buttons.iter() {
+        if let MenuButtonAction::Exit = action {
+            focus.focus(entity);
+        }
+    }
+}
+
+fn players_label(players: usize) -> String {
+    format!("Players: {}", players)
+}
+
+fn mutators_label(mutators: Mutators) -> String {
+    if mutators.is_empty() {
+        "Mutators: none".to_string()
+    } else {
+        format!("Mutators: {}", mutators.names().join(", "))
+    }
+}
+
+fn theme_label(themes: &Themes, settings: &Settings) -> String {
+    format!("Theme: {}", themes.name(theme::selected(settings)))
+}
+
+fn top_scores_label(top_scores: &TopScores) -> String {
+    match top_scores {
+        TopScores::Loading => "Top Scores\n\nloading...".to_string(),
+        TopScores::Offline => "Top Scores\n\noffline".to_string(),
+        TopScores::Loaded(entries) if entries.is_empty() => "Top Scores\n\nnone yet".to_string(),
+        TopScores::Loaded(entries) => {
+            let mut label = "Top Scores\n".to_string();
+            for (rank, entry) in entries.iter().take(TOP_SCORES).enumerate() {
+                label += &format!("\n{:>2}. {} {}", rank + 1, entry.name, entry.score);
+            }
+            label
+        }
+    }
+}
+
+//days shown in the daily challenge calendar
+const CALENDAR_DAYS: usize = 7;
+
+//the last week of daily challenges, a dash for days that weren't played
+fn calendar_label(daily: &Daily, today: Date) -> String {
+    let mut label = format!("Daily Challenge\n{} {}\n", today.month_name(), today.year);
+    for (date, best) in daily.calendar(today, CALENDAR_DAYS) {
+        let best = best.map_or("-".to_string(), |best| best.to_string());
+        let day = if date == today {
+            "today".to_string()
+        } else {
+            date.to_string()
+        };
+        label += &format!("\n{:<10} {:>4}", day, best);
+    }
+    label
+}
+
+//picking a theme changes the backdrop behind the menu straight away
+fn show_theme(theme: Res<Theme>, mut backdrop: ResMut<ClearColor>) {
+    backdrop.0 = theme::colour(theme.menu_colour);
+}
+
+//updates the list when a fetch finishes
+fn show_top_scores(top_scores: Res<TopScores>, mut text: Query<&mut Text, With<TopScoresText>>) {
+    for mut text in text.iter_mut() {
+        text.sections[0].value = top_scores_label(&top_scores);
+    }
+}
+
 //This function took me a solid day to do
 //Why does UI suck
 //sets up the mainmenu
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut backdrop: ResMut<ClearColor>) {
-    backdrop.0 = Color::GRAY;
+#[allow(clippy::too_many_arguments)]
+fn setup(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    mut backdrop: ResMut<ClearColor>,
+    players: Res<Players>,
+    mutators: Res<SelectedMutators>,
+    top_scores: Res<TopScores>,
+    daily: Res<Daily>,
+    theme: Res<Theme>,
+    themes: Res<Themes>,
+    settings: Res<Settings>,
+) {
+    backdrop.0 = theme::colour(theme.menu_colour);
 
-    let font: Handle<Font> = asset_server.load("fonts/blocky.ttf");
+    let font: Handle<Font> = assets.font.clone();
 
     let button_style = Style {
         width: Val::Px(250.0),
         height: Val::Px(65.0),
-        margin: UiRect::all(Val::Px(20.0)),
+        margin: UiRect::all(Val::Px(15.0)),
         justify_content: JustifyContent::Center,
         align_items: AlignItems::Center,
         ..default()
@@ -100,6 +307,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut backdrop: R
                 ..default()
             },
             MainMenuComp,
+            FocusGroup,
         ))
         .with_children(|parent| {
             parent
@@ -116,13 +324,13 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut backdrop: R
                         TextBundle::from_section(
                             "Flappy Bird",
                             TextStyle {
-                                font: font,
+                                font,
                                 font_size: 100.,
                                 ..default()
                             },
                         )
                         .with_style(Style {
-                            margin: UiRect::all(Val::Px(50.0)),
+                            margin: UiRect::all(Val::Px(30.0)),
                             ..default()
                         }),
                     );
@@ -143,6 +351,144 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut backdrop: R
                             ));
                         });
 
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: button_style.clone(),
+                                background_color: NORMAL_BUTTON.into(),
+                                ..default()
+                            },
+                            MenuButtonAction::Daily,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                "Daily".to_string(),
+                                button_text_style.clone(),
+                            ));
+                        });
+
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: button_style.clone(),
+                                background_color: NORMAL_BUTTON.into(),
+                                ..default()
+                            },
+                            MenuButtonAction::Code,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                "Code".to_string(),
+                                button_text_style.clone(),
+                            ));
+                        });
+
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: button_style.clone(),
+                                background_color: NORMAL_BUTTON.into(),
+                                ..default()
+                            },
+                            MenuButtonAction::Players,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                TextBundle::from_section(
+                                    players_label(players.0),
+                                    button_text_style.clone(),
+                                ),
+                                PlayersText,
+                            ));
+                        });
+
+                    //grows to fit the names of whatever is on
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Auto,
+                                    min_width: Val::Px(250.0),
+                                    padding: UiRect::horizontal(Val::Px(20.0)),
+                                    ..button_style.clone()
+                                },
+                                background_color: NORMAL_BUTTON.into(),
+                                ..default()
+                            },
+                            MenuButtonAction::Mutators,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                TextBundle::from_section(
+                                    mutators_label(mutators.0),
+                                    TextStyle {
+                                        font_size: 30.0,
+                                        ..button_text_style.clone()
+                                    },
+                                ),
+                                MutatorsText,
+                            ));
+                        });
+
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Auto,
+                                    min_width: Val::Px(250.0),
+                                    padding: UiRect::horizontal(Val::Px(20.0)),
+                                    ..button_style.clone()
+                                },
+                                background_color: NORMAL_BUTTON.into(),
+                                ..default()
+                            },
+                            MenuButtonAction::Theme,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                TextBundle::from_section(
+                                    theme_label(&themes, &settings),
+                                    TextStyle {
+                                        font_size: 30.0,
+                                        ..button_text_style.clone()
+                                    },
+                                ),
+                                ThemeText,
+                            ));
+                        });
+
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: button_style.clone(),
+                                background_color: NORMAL_BUTTON.into(),
+                                ..default()
+                            },
+                            MenuButtonAction::Autopilot,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                "Autopilot".to_string(),
+                                button_text_style.clone(),
+                            ));
+                        });
+
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: button_style.clone(),
+                                background_color: NORMAL_BUTTON.into(),
+                                ..default()
+                            },
+                            MenuButtonAction::Settings,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                "Settings".to_string(),
+                                button_text_style.clone(),
+                            ));
+                        });
+
                     parent
                         .spawn((
                             ButtonBundle {
@@ -159,6 +505,32 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut backdrop: R
                             ));
                         });
                 });
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        margin: UiRect::left(Val::Px(100.0)),
+                        row_gap: Val::Px(50.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    let text_style = TextStyle {
+                        font_size: 30.0,
+                        color: Color::WHITE,
+                        ..default()
+                    };
+                    parent.spawn((
+                        TextBundle::from_section(top_scores_label(&top_scores), text_style.clone()),
+                        TopScoresText,
+                    ));
+                    parent.spawn(TextBundle::from_section(
+                        calendar_label(&daily, Date::today()),
+                        text_style,
+                    ));
+                });
         });
 }
 
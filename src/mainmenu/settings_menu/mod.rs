@@ -0,0 +1,519 @@
+use super::{
+    focus::{Focus, FocusGroup},
+    MainMenuComp, HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON,
+};
+use crate::{
+    game::{
+        input::{self, Action, Actions, Buttons},
+        settings::Settings,
+        MAX_PLAYERS,
+    },
+    loading::GameAssets,
+    AppState,
+};
+use bevy::{
+    prelude::*,
+    ui::{FocusPolicy, RelativeCursorPosition},
+};
+use flappybird::config::{Config, ControlsConfig, WindowMode};
+
+//a screen over the main menu for the options in the config file, every change is saved and
+//shows up straight away
+pub struct SettingsMenuPlugin;
+
+impl Plugin for SettingsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SettingsMenu>()
+            .add_systems(OnExit(AppState::MainMenu), close)
+            .add_systems(
+                Update,
+                (
+                    go_back,
+                    rebind.run_if(|menu: Res<SettingsMenu>| menu.rebinding.is_some()),
+                    button_system,
+                    hold_focus.run_if(resource_changed::<SettingsMenu>),
+                    drag_sliders,
+                    nudge_sliders,
+                    show_panel.run_if(resource_changed::<SettingsMenu>),
+                    show_settings.run_if(
+                        resource_changed::<Settings>.or_else(resource_changed::<SettingsMenu>),
+                    ),
+                )
+                    .chain()
+                    .run_if(in_state(AppState::MainMenu)),
+            );
+    }
+}
+
+//window sizes to pick from, the game scales to fit any of them
+const RESOLUTIONS: [[u32; 2]; 5] = [
+    [1280, 720],
+    [1600, 900],
+    [1920, 1080],
+    [2560, 1440],
+    [3840, 2160],
+];
+
+//volumes move in steps this big so dragging doesn't save a new file every frame
+const VOLUME_STEP: f32 = 0.05;
+
+const SLIDER_WIDTH: f32 = 300.;
+
+#[derive(Resource, Default)]
+pub struct SettingsMenu {
+    open: bool,
+    //player whose flap key is waiting on the next key press
+    rebinding: Option<usize>,
+}
+
+impl SettingsMenu {
+    pub fn open(&mut self) {
+        *self = SettingsMenu {
+            open: true,
+            ..default()
+        };
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Setting {
+    WindowMode,
+    Resolution,
+    Vsync,
+    MasterVolume,
+    MusicVolume,
+    EffectsVolume,
+    Flap(usize),
+    ReducedMotion,
+    Colourblind,
+}
+
+//like MenuButtonAction on the main menu
+#[derive(Component)]
+enum SettingsButtonAction {
+    Change(Setting),
+    Back,
+}
+
+#[derive(Component)]
+struct SettingsPanel;
+
+//text that shows a setting's current value
+#[derive(Component)]
+struct SettingText(Setting);
+
+//a bar to click or drag along to pick a volume
+#[derive(Component)]
+struct Slider(Setting);
+
+//the filled part of a slider
+#[derive(Component)]
+struct SliderFill(Setting);
+
+fn close(mut menu: ResMut<SettingsMenu>) {
+    menu.open = false;
+}
+
+fn on_off(on: bool) -> &'static str {
+    if on {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+fn volume(config: &Config, setting: Setting) -> Option<f32> {
+    match setting {
+        Setting::MasterVolume => Some(config.audio.master),
+        Setting::MusicVolume => Some(config.audio.music),
+        Setting::EffectsVolume => Some(config.audio.effects),
+        _ => None,
+    }
+}
+
+fn set_volume(config: &mut Config, setting: Setting, value: f32) {
+    match setting {
+        Setting::MasterVolume => config.audio.master = value,
+        Setting::MusicVolume => config.audio.music = value,
+        Setting::EffectsVolume => config.audio.effects = value,
+        _ => (),
+    }
+}
+
+fn flap_name(config: &Config, player: usize) -> String {
+    let names = config
+        .controls
+        .flap
+        .get(player)
+        .cloned()
+        .unwrap_or_else(|| ControlsConfig::default().flap[player].clone());
+    if names.is_empty() {
+        "nothing".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+fn label(setting: Setting, config: &Config, menu: &SettingsMenu) -> String {
+    let [width, height] = config.display.resolution;
+    match setting {
+        Setting::WindowMode => format!("Window: {}", config.display.mode.name()),
+        Setting::Resolution => format!("Resolution: {}x{}", width, height),
+        Setting::Vsync => format!("Vsync: {}", on_off(config.display.vsync)),
+        Setting::MasterVolume => format!("Master: {:.0}%", config.audio.master * 100.),
+        Setting::MusicVolume => format!("Music: {:.0}%", config.audio.music * 100.),
+        Setting::EffectsVolume => format!("Effects: {:.0}%", config.audio.effects * 100.),
+        Setting::Flap(player) if menu.rebinding == Some(player) => {
+            format!("Player {} flap: press a key or button", player + 1)
+        }
+        Setting::Flap(player) => {
+            format!("Player {} flap: {}", player + 1, flap_name(config, player))
+        }
+        Setting::ReducedMotion => {
+            format!(
+                "Reduced motion: {}",
+                on_off(config.accessibility.reduced_motion)
+            )
+        }
+        Setting::Colourblind => format!(
+            "Colourblind colours: {}",
+            on_off(config.accessibility.colourblind)
+        ),
+    }
+}
+
+//what a click does, sliders are dragged instead
+fn change(setting: Setting, settings: &mut Settings, menu: &mut SettingsMenu) {
+    let display = &mut settings.display;
+    match setting {
+        Setting::WindowMode => {
+            let index = WindowMode::ALL
+                .iter()
+                .position(|mode| *mode == display.mode);
+            display.mode =
+                WindowMode::ALL[index.map_or(0, |index| (index + 1) % WindowMode::ALL.len())];
+        }
+        Setting::Resolution => {
+            let index = RESOLUTIONS
+                .iter()
+                .position(|resolution| *resolution == display.resolution);
+            display.resolution =
+                RESOLUTIONS[index.map_or(0, |index| (index + 1) % RESOLUTIONS.len())];
+        }
+        Setting::Vsync => display.vsync = !display.vsync,
+        Setting::Flap(player) => menu.rebinding = Some(player),
+        Setting::ReducedMotion => {
+            settings.accessibility.reduced_motion = !settings.accessibility.reduced_motion
+        }
+        Setting::Colourblind => {
+            settings.accessibility.colourblind = !settings.accessibility.colourblind
+        }
+        Setting::MasterVolume | Setting::MusicVolume | Setting::EffectsVolume => (),
+    }
+}
+
+//back gets out of picking a binding first, then out of the screen
+fn go_back(actions: Res<Actions>, mut menu: ResMut<SettingsMenu>) {
+    if menu.open && menu.rebinding.is_none() && actions.just_pressed(Action::Back) {
+        menu.open = false;
+    }
+}
+
+//the new key or gamepad button takes over from the old one on the same device
+//mouse buttons can only be set in the config file since clicking is how this screen is used
+fn rebind(
+    buttons: Buttons,
+    actions: Res<Actions>,
+    mut menu: ResMut<SettingsMenu>,
+    mut settings: ResMut<Settings>,
+) {
+    let Some(player) = menu.rebinding else {
+        return;
+    };
+    if actions.just_pressed(Action::Back) {
+        menu.rebinding = None;
+    } else if let Some(binding) = buttons.any_just_pressed() {
+        input::rebind(&mut settings, player, binding);
+        menu.rebinding = None;
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn button_system(
+    mut interaction_query: Query<
+        (
+            &Interaction,
+            &mut BackgroundColor,
+            &mut BorderColor,
+            &SettingsButtonAction,
+        ),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut settings: ResMut<Settings>,
+    mut menu: ResMut<SettingsMenu>,
+) {
+    for (interaction, mut color, mut border_color, action) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = PRESSED_BUTTON.into();
+                border_color.0 = Color::RED;
+                match action {
+                    SettingsButtonAction::Change(setting) => {
+                        change(*setting, &mut settings, &mut menu)
+                    }
+                    SettingsButtonAction::Back => menu.open = false,
+                }
+            }
+            Interaction::Hovered => {
+                *color = HOVERED_BUTTON.into();
+                border_color.0 = Color::WHITE;
+            }
+            Interaction::None => {
+                *color = NORMAL_BUTTON.into();
+                border_color.0 = Color::BLACK;
+            }
+        }
+    }
+}
+
+//keys picking a binding shouldn't move around the screen as well
+fn hold_focus(menu: Res<SettingsMenu>, mut focus: ResMut<Focus>) {
+    focus.held = menu.rebinding.is_some();
+}
+
+//holding the mouse down on a slider keeps following it
+//the mouse has to be down as confirm presses the slider too
+fn drag_sliders(
+    sliders: Query<(&Interaction, &RelativeCursorPosition, &Slider)>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut settings: ResMut<Settings>,
+) {
+    if !mouse.pressed(MouseButton::Left) {
+        return;
+    }
+    for (interaction, cursor, slider) in sliders.iter() {
+        let (Interaction::Pressed, Some(cursor)) = (interaction, cursor.normalized) else {
+            continue;
+        };
+        let value = (cursor.x.clamp(0., 1.) / VOLUME_STEP).round() * VOLUME_STEP;
+        //only counts as a change when it moves a step so the file isn't saved every frame
+        if volume(&settings, slider.0).is_some_and(|volume| volume != value) {
+            set_volume(&mut settings, slider.0, value);
+        }
+    }
+}
+
+//left and right move the slider with the focus a step
+fn nudge_sliders(
+    actions: Res<Actions>,
+    focus: Res<Focus>,
+    menu: Res<SettingsMenu>,
+    sliders: Query<&Slider>,
+    mut settings: ResMut<Settings>,
+) {
+    let Some(slider) = focus
+        .focused()
+        .and_then(|focused| sliders.get(focused).ok())
+    else {
+        return;
+    };
+    let step = if actions.just_pressed(Action::Left) {
+        -VOLUME_STEP
+    } else if actions.just_pressed(Action::Right) {
+        VOLUME_STEP
+    } else {
+        return;
+    };
+    if menu.rebinding.is_some() {
+        return;
+    }
+    if let Some(volume) = volume(&settings, slider.0) {
+        let value = ((volume + step).clamp(0., 1.) / VOLUME_STEP).round() * VOLUME_STEP;
+        set_volume(&mut settings, slider.0, value);
+    }
+}
+
+fn show_settings(
+    settings: Res<Settings>,
+    menu: Res<SettingsMenu>,
+    mut texts: Query<(&mut Text, &SettingText)>,
+    mut fills: Query<(&mut Style, &SliderFill)>,
+) {
+    for (mut text, setting) in texts.iter_mut() {
+        text.sections[0].value = label(setting.0, &settings, &menu);
+    }
+    for (mut style, fill) in fills.iter_mut() {
+        let value = volume(&settings, fill.0).unwrap_or_default();
+        style.width = Val::Percent(value.clamp(0., 1.) * 100.);
+    }
+}
+
+//opens and closes the panel to match the menu
+fn show_panel(
+    mut commands: Commands,
+    menu: Res<SettingsMenu>,
+    panel: Query<Entity, With<SettingsPanel>>,
+    assets: Res<GameAssets>,
+) {
+    if !menu.open {
+        for entity in panel.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+    if panel.is_empty() {
+        spawn_panel(&mut commands, assets.font.clone());
+    }
+}
+
+fn spawn_panel(commands: &mut Commands, font: Handle<Font>) {
+    let button_style = Style {
+        width: Val::Px(700.0),
+        height: Val::Px(50.0),
+        margin: UiRect::all(Val::Px(6.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+    let text_style = TextStyle {
+        font_size: 28.0,
+        color: Color::WHITE,
+        ..default()
+    };
+    let button = |parent: &mut ChildBuilder, setting: Setting| {
+        parent
+            .spawn((
+                ButtonBundle {
+                    style: button_style.clone(),
+                    background_color: NORMAL_BUTTON.into(),
+                    ..default()
+                },
+                SettingsButtonAction::Change(setting),
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    TextBundle::from_section("", text_style.clone()),
+                    SettingText(setting),
+                ));
+            });
+    };
+    let slider = |parent: &mut ChildBuilder, setting: Setting| {
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    width: button_style.width,
+                    height: button_style.height,
+                    margin: button_style.margin,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::SpaceBetween,
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn((
+                    TextBundle::from_section("", text_style.clone()),
+                    SettingText(setting),
+                ));
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(SLIDER_WIDTH),
+                                height: Val::Px(24.0),
+                                ..default()
+                            },
+                            background_color: NORMAL_BUTTON.into(),
+                            ..default()
+                        },
+                        Slider(setting),
+                        RelativeCursorPosition::default(),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            NodeBundle {
+                                style: Style {
+                                    height: Val::Percent(100.0),
+                                    ..default()
+                                },
+                                background_color: Color::WHITE.into(),
+                                //clicks land on the bar underneath
+                                focus_policy: FocusPolicy::Pass,
+                                ..default()
+                            },
+                            SliderFill(setting),
+                        ));
+                    });
+            });
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0., 0., 0., 0.85).into(),
+                //stops clicks going through to the menu underneath
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            SettingsPanel,
+            MainMenuComp,
+            FocusGroup,
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    "Settings",
+                    TextStyle {
+                        font,
+                        font_size: 70.,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                }),
+            );
+            for setting in [Setting::WindowMode, Setting::Resolution, Setting::Vsync] {
+                button(parent, setting);
+            }
+            for setting in [
+                Setting::MasterVolume,
+                Setting::MusicVolume,
+                Setting::EffectsVolume,
+            ] {
+                slider(parent, setting);
+            }
+            for player in 0..MAX_PLAYERS {
+                button(parent, Setting::Flap(player));
+            }
+            for setting in [Setting::ReducedMotion, Setting::Colourblind] {
+                button(parent, setting);
+            }
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            margin: UiRect::top(Val::Px(20.0)),
+                            ..button_style.clone()
+                        },
+                        background_color: NORMAL_BUTTON.into(),
+                        ..default()
+                    },
+                    SettingsButtonAction::Back,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section("Back", text_style.clone()));
+                });
+        });
+}
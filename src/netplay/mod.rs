@@ -0,0 +1,465 @@
+//Lockstep versus over UDP
+//Both games run the same seed and only send each other their flaps for every tick.
+//Neither side steps a tick until it has both birds' input for it, so the two simulations stay identical.
+//Every packet repeats all the inputs the other side hasn't acknowledged yet so dropped packets don't matter,
+//and every so often the sides swap world checksums so a desync gets noticed instead of silently drifting
+use crate::sim::World;
+use std::{
+    collections::HashMap,
+    io,
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+    time::{Duration, Instant},
+};
+
+//ticks between local input and it being played, gives the packet time to get there before it's needed
+pub const INPUT_DELAY: u64 = 3;
+//ticks between checksum comparisons
+pub const CHECKSUM_INTERVAL: u64 = 60;
+//most unacknowledged inputs sent in one packet
+const MAX_INPUTS_PER_PACKET: usize = 64;
+//give up if nothing has been heard for this long
+const TIMEOUT: Duration = Duration::from_secs(10);
+//how often the handshake is retried
+const HANDSHAKE_RETRY: Duration = Duration::from_millis(200);
+
+//first bytes of every packet so stray traffic on the port is ignored
+const MAGIC: [u8; 4] = *b"FBV1";
+const HELLO: u8 = 0;
+const WELCOME: u8 = 1;
+const INPUTS: u8 = 2;
+
+//host flies bird 0 and the guest bird 1
+pub const HOST: usize = 0;
+pub const GUEST: usize = 1;
+
+pub struct Session {
+    socket: UdpSocket,
+    peer: SocketAddr,
+    seed: u64,
+    //which bird this side flies
+    local: usize,
+    //inputs by tick, local ones are known up to local_inputs.len() - 1
+    local_inputs: Vec<bool>,
+    remote_inputs: Vec<bool>,
+    //the highest tick the other side has told us it has of our inputs, plus one
+    remote_acked: u64,
+    //checksums waiting on the other side's for the same tick
+    local_checksums: HashMap<u64, u64>,
+    remote_checksums: HashMap<u64, u64>,
+    //the newest of ours, kept to send after its been compared as the other side might not have it yet
+    latest_checksum: Option<(u64, u64)>,
+    //checksums up to this tick have been compared and dropped
+    confirmed: Option<u64>,
+    desync: Option<u64>,
+    last_heard: Instant,
+}
+
+impl Session {
+    //waits for a guest to say hello on the port and tells them the seed
+    pub fn host(port: u16, seed: u64, wait: Duration) -> io::Result<Session> {
+        let socket = UdpSocket::bind(("0.0.0.0", port))?;
+        socket.set_read_timeout(Some(HANDSHAKE_RETRY))?;
+        let started = Instant::now();
+        let mut buffer = [0; 1024];
+        while started.elapsed() < wait {
+            match socket.recv_from(&mut buffer) {
+                Ok((len, peer)) if packet_kind(&buffer[..len]) == Some(HELLO) => {
+                    let session = Session::new(socket, peer, seed, HOST)?;
+                    session.welcome()?;
+                    return Ok(session);
+                }
+                Ok(_) => (),
+                Err(err) if is_timeout(&err) => (),
+                Err(err) => return Err(err),
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            "nobody joined in time",
+        ))
+    }
+
+    //says hello to a host until it answers with the seed
+    pub fn join(address: impl ToSocketAddrs, wait: Duration) -> io::Result<Session> {
+        //prefer ipv4 as the host listens on 0.0.0.0 and localhost often resolves to ::1 first
+        let addresses: Vec<SocketAddr> = address.to_socket_addrs()?.collect();
+        let peer = addresses
+            .iter()
+            .find(|address| address.is_ipv4())
+            .or(addresses.first())
+            .copied()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address to join"))?;
+        let bind: SocketAddr = if peer.is_ipv4() {
+            "0.0.0.0:0".parse().expect("valid address")
+        } else {
+            "[::]:0".parse().expect("valid address")
+        };
+        let socket = UdpSocket::bind(bind)?;
+        socket.set_read_timeout(Some(HANDSHAKE_RETRY))?;
+        let started = Instant::now();
+        let mut buffer = [0; 1024];
+        while started.elapsed() < wait {
+            socket.send_to(&header(HELLO), peer)?;
+            match socket.recv_from(&mut buffer) {
+                Ok((len, from)) if from == peer && packet_kind(&buffer[..len]) == Some(WELCOME) => {
+                    let seed = read_u64(&buffer[..len], MAGIC.len() + 1).ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidData, "bad welcome packet")
+                    })?;
+                    return Session::new(socket, peer, seed, GUEST);
+                }
+                Ok(_) => (),
+                Err(err) if is_timeout(&err) => (),
+                Err(err) => return Err(err),
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            "the host didn't answer in time",
+        ))
+    }
+
+    fn new(socket: UdpSocket, peer: SocketAddr, seed: u64, local: usize) -> io::Result<Session> {
+        socket.set_nonblocking(true)?;
+        //nobody flaps during the input delay at the start
+        let delay = vec![false; INPUT_DELAY as usize];
+        Ok(Session {
+            socket,
+            peer,
+            seed,
+            local,
+            local_inputs: delay.clone(),
+            remote_inputs: delay,
+            remote_acked: 0,
+            local_checksums: HashMap::new(),
+            remote_checksums: HashMap::new(),
+            latest_checksum: None,
+            confirmed: None,
+            desync: None,
+            last_heard: Instant::now(),
+        })
+    }
+
+    //tells the guest the seed, sent again whenever it says hello in case the first one got lost
+    fn welcome(&self) -> io::Result<()> {
+        let mut welcome = header(WELCOME);
+        welcome.extend_from_slice(&self.seed.to_le_bytes());
+        match self.socket.send_to(&welcome, self.peer) {
+            Err(err) if err.kind() != io::ErrorKind::WouldBlock => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn local_bird(&self) -> usize {
+        self.local
+    }
+
+    pub fn remote_bird(&self) -> usize {
+        1 - self.local
+    }
+
+    //the tick a flap pressed now will be played on
+    pub fn next_local_tick(&self) -> u64 {
+        self.local_inputs.len() as u64
+    }
+
+    //queues this side's input for a tick, only ever runs INPUT_DELAY ticks ahead of the simulation
+    pub fn queue_local(&mut self, current_tick: u64, flap: bool) -> bool {
+        if self.next_local_tick() > current_tick + INPUT_DELAY {
+            return false;
+        }
+        self.local_inputs.push(flap);
+        true
+    }
+
+    //flaps for both birds on a tick, none if we're still waiting on the other side
+    pub fn flaps(&self, tick: u64) -> Option<[bool; 2]> {
+        let local = *self.local_inputs.get(tick as usize)?;
+        let remote = *self.remote_inputs.get(tick as usize)?;
+        let mut flaps = [false; 2];
+        flaps[self.local] = local;
+        flaps[self.remote_bird()] = remote;
+        Some(flaps)
+    }
+
+    //remembers the world after a tick and checks it against the other side
+    pub fn record_checksum(&mut self, world: &World) {
+        if world.tick().is_multiple_of(CHECKSUM_INTERVAL) {
+            self.local_checksums.insert(world.tick(), world.checksum());
+            self.latest_checksum = Some((world.tick(), world.checksum()));
+            self.compare_checksums();
+        }
+    }
+
+    //the first tick the two sides disagreed on, if they have
+    pub fn desync(&self) -> Option<u64> {
+        self.desync
+    }
+
+    pub fn timed_out(&self) -> bool {
+        self.last_heard.elapsed() > TIMEOUT
+    }
+
+    //reads everything waiting on the socket
+    pub fn receive(&mut self) -> io::Result<()> {
+        let mut buffer = [0; 2048];
+        loop {
+            match self.socket.recv_from(&mut buffer) {
+                Ok((len, from)) if from == self.peer => {
+                    if self.local == HOST && packet_kind(&buffer[..len]) == Some(HELLO) {
+                        self.welcome()?;
+                    }
+                    self.read_packet(&buffer[..len]);
+                }
+                Ok(_) => (),
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                //windows reports the other side closing as an error on the next read
+                Err(err) if err.kind() == io::ErrorKind::ConnectionReset => return Ok(()),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    //sends every input the other side hasn't acknowledged plus our latest checksum
+    pub fn send(&mut self) -> io::Result<()> {
+        match self.socket.send_to(&self.inputs_packet(), self.peer) {
+            Ok(_) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::ConnectionRefused => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn inputs_packet(&self) -> Vec<u8> {
+        let first = self.remote_acked;
+        let inputs = &self.local_inputs[(first as usize).min(self.local_inputs.len())..];
+        let inputs = &inputs[..inputs.len().min(MAX_INPUTS_PER_PACKET)];
+        let mut packet = header(INPUTS);
+        packet.extend_from_slice(&(self.remote_inputs.len() as u64).to_le_bytes());
+        let (checksum_tick, checksum) = self.latest_checksum.unwrap_or((u64::MAX, 0));
+        packet.extend_from_slice(&checksum_tick.to_le_bytes());
+        packet.extend_from_slice(&checksum.to_le_bytes());
+        packet.extend_from_slice(&first.to_le_bytes());
+        packet.push(inputs.len() as u8);
+        packet.extend(inputs.iter().map(|flap| *flap as u8));
+        packet
+    }
+
+    fn read_packet(&mut self, packet: &[u8]) {
+        if packet_kind(packet) != Some(INPUTS) {
+            return;
+        }
+        let start = MAGIC.len() + 1;
+        let (Some(ack), Some(checksum_tick), Some(checksum), Some(first), Some(count)) = (
+            read_u64(packet, start),
+            read_u64(packet, start + 8),
+            read_u64(packet, start + 16),
+            read_u64(packet, start + 24),
+            packet.get(start + 32),
+        ) else {
+            return;
+        };
+        let Some(inputs) = packet.get(start + 33..start + 33 + *count as usize) else {
+            return;
+        };
+        self.last_heard = Instant::now();
+        self.remote_acked = self.remote_acked.max(ack);
+        //only take inputs that carry on from what we've got, older ones are repeats
+        for (offset, flap) in inputs.iter().enumerate() {
+            if first + offset as u64 == self.remote_inputs.len() as u64 {
+                self.remote_inputs.push(*flap != 0);
+            }
+        }
+        //repeats of ones already compared are dropped so they don't pile up
+        if checksum_tick != u64::MAX
+            && self
+                .confirmed
+                .is_none_or(|confirmed| checksum_tick > confirmed)
+        {
+            self.remote_checksums.insert(checksum_tick, checksum);
+            self.compare_checksums();
+        }
+    }
+
+    //once both sides have a tick it's compared and everything up to it dropped
+    //older ones the other side never sent can't be compared any more
+    fn compare_checksums(&mut self) {
+        for (tick, remote) in self.remote_checksums.iter() {
+            if let Some(local) = self.local_checksums.get(tick) {
+                if local != remote && self.desync.is_none_or(|desync| *tick < desync) {
+                    self.desync = Some(*tick);
+                }
+                self.confirmed = self.confirmed.max(Some(*tick));
+            }
+        }
+        if let Some(confirmed) = self.confirmed {
+            self.local_checksums.retain(|tick, _| *tick > confirmed);
+            self.remote_checksums.retain(|tick, _| *tick > confirmed);
+        }
+    }
+}
+
+fn header(kind: u8) -> Vec<u8> {
+    let mut packet = MAGIC.to_vec();
+    packet.push(kind);
+    packet
+}
+
+fn packet_kind(packet: &[u8]) -> Option<u8> {
+    if packet.starts_with(&MAGIC) {
+        packet.get(MAGIC.len()).copied()
+    } else {
+        None
+    }
+}
+
+fn read_u64(packet: &[u8], at: usize) -> Option<u64> {
+    let bytes = packet.get(at..at + 8)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn is_timeout(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::{Rng, Rules};
+    use std::collections::VecDeque;
+
+    //one end of a match with the game it's playing
+    struct Side {
+        session: Session,
+        world: World,
+        //the world's checksum after every tick it played
+        checksums: Vec<u64>,
+        rng: Rng,
+    }
+
+    impl Side {
+        //the seeds can differ from the session's to force a desync
+        fn new(local: usize, world_seed: u64) -> Side {
+            let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+            let peer = socket.local_addr().unwrap();
+            //no floor so nobody dies and the match keeps going the whole test
+            let rules = Rules {
+                solid_edges: false,
+                ..Rules::default()
+            };
+            Side {
+                session: Session::new(socket, peer, 1, local).unwrap(),
+                world: World::new(rules, world_seed, 2),
+                checksums: Vec::new(),
+                rng: Rng::new(local as u64 + 100),
+            }
+        }
+
+        //one frame of the game, everything it sends goes on the outbox
+        fn frame(&mut self, inbox: &mut VecDeque<Vec<u8>>, outbox: &mut Vec<Vec<u8>>) {
+            while let Some(packet) = inbox.pop_front() {
+                self.session.read_packet(&packet);
+            }
+            let tick = self.world.tick();
+            let flap = self.rng.range(0., 1.) < 0.05;
+            self.session.queue_local(tick, flap);
+            outbox.push(self.session.inputs_packet());
+            if let Some(flaps) = self.session.flaps(tick) {
+                for (bird, flap) in flaps.into_iter().enumerate() {
+                    if flap {
+                        self.world.flap(bird);
+                    }
+                }
+                self.world.step();
+                self.session.record_checksum(&self.world);
+                self.checksums.push(self.world.checksum());
+            }
+        }
+    }
+
+    //drops, repeats and swaps packets around at the given rate each
+    fn deliver(packets: Vec<Vec<u8>>, to: &mut VecDeque<Vec<u8>>, rng: &mut Rng, rate: f32) {
+        for packet in packets {
+            if rng.range(0., 1.) < rate {
+                continue;
+            }
+            if rng.range(0., 1.) < rate {
+                to.push_back(packet.clone());
+            }
+            if rng.range(0., 1.) < rate {
+                to.push_front(packet);
+            } else {
+                to.push_back(packet);
+            }
+        }
+    }
+
+    //runs both sides for a number of frames over a link that loses some packets
+    fn play(host: &mut Side, guest: &mut Side, frames: usize, loss: f32) {
+        let mut rng = Rng::new(7);
+        let (mut to_host, mut to_guest) = (VecDeque::new(), VecDeque::new());
+        for _ in 0..frames {
+            let mut sent = Vec::new();
+            host.frame(&mut to_host, &mut sent);
+            deliver(sent, &mut to_guest, &mut rng, loss);
+            let mut sent = Vec::new();
+            guest.frame(&mut to_guest, &mut sent);
+            deliver(sent, &mut to_host, &mut rng, loss);
+        }
+    }
+
+    #[test]
+    fn lossy_link_plays_the_same_ticks_on_both_sides() {
+        let (mut host, mut guest) = (Side::new(HOST, 1), Side::new(GUEST, 1));
+        play(&mut host, &mut guest, 3000, 0.3);
+        let played = host.checksums.len().min(guest.checksums.len());
+        assert!(played > 1000, "only {} ticks got played", played);
+        assert_eq!(host.checksums[..played], guest.checksums[..played]);
+        //every input that got across is what the other side pressed, flaps included
+        for (from, to) in [(&host, &guest), (&guest, &host)] {
+            let received = &to.session.remote_inputs;
+            assert_eq!(received[..], from.session.local_inputs[..received.len()]);
+            assert!(received.contains(&true));
+        }
+        assert_eq!(host.session.desync(), None);
+        assert_eq!(guest.session.desync(), None);
+    }
+
+    #[test]
+    fn compared_checksums_get_dropped() {
+        let (mut host, mut guest) = (Side::new(HOST, 1), Side::new(GUEST, 1));
+        play(&mut host, &mut guest, 3000, 0.3);
+        for session in [&host.session, &guest.session] {
+            assert!(session.confirmed.is_some());
+            assert!(session.local_checksums.len() <= 2);
+            assert!(session.remote_checksums.len() <= 2);
+        }
+    }
+
+    #[test]
+    fn different_worlds_are_reported_as_a_desync() {
+        let (mut host, mut guest) = (Side::new(HOST, 1), Side::new(GUEST, 2));
+        play(&mut host, &mut guest, 300, 0.);
+        assert_eq!(host.session.desync(), Some(CHECKSUM_INTERVAL));
+        assert_eq!(guest.session.desync(), Some(CHECKSUM_INTERVAL));
+    }
+
+    #[test]
+    fn times_out_once_the_other_side_goes_quiet() {
+        let (mut host, mut guest) = (Side::new(HOST, 1), Side::new(GUEST, 1));
+        assert!(!host.session.timed_out());
+        host.session.last_heard = Instant::now() - TIMEOUT - Duration::from_millis(1);
+        assert!(host.session.timed_out());
+        //hearing anything at all from them again resets it
+        let mut sent = Vec::new();
+        guest.frame(&mut VecDeque::new(), &mut sent);
+        host.frame(&mut sent.into(), &mut Vec::new());
+        assert!(!host.session.timed_out());
+    }
+}
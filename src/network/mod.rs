@@ -0,0 +1,107 @@
+//Tiny neural network policy for the bird, evolved by the train binary and loaded by the game as a bot
+//one hidden layer, flaps whenever the output is above zero
+use crate::sim::{env::Observation, Rng};
+use std::{fs, io, path::Path};
+
+pub const INPUTS: usize = 6;
+pub const HIDDEN: usize = 8;
+//every hidden neuron has a weight per input plus a bias, the output has one per hidden neuron plus a bias
+pub const WEIGHTS: usize = HIDDEN * (INPUTS + 1) + HIDDEN + 1;
+
+//first line of a saved genome so we don't load some random file as weights
+const HEADER: &str = "flappybird-genome 1";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Network {
+    weights: Vec<f32>,
+}
+
+impl Network {
+    pub fn random(rng: &mut Rng) -> Self {
+        Network {
+            weights: (0..WEIGHTS).map(|_| rng.normal()).collect(),
+        }
+    }
+
+    pub fn from_weights(weights: Vec<f32>) -> io::Result<Self> {
+        if weights.len() != WEIGHTS {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("expected {} weights, got {}", WEIGHTS, weights.len()),
+            ));
+        }
+        Ok(Network { weights })
+    }
+
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    pub fn output(&self, observation: &Observation) -> f32 {
+        let inputs = observation.to_inputs();
+        let (hidden_weights, output_weights) = self.weights.split_at(HIDDEN * (INPUTS + 1));
+        let mut output = output_weights[HIDDEN];
+        for (neuron, weights) in hidden_weights.chunks(INPUTS + 1).enumerate() {
+            let sum = weights[INPUTS]
+                + inputs
+                    .iter()
+                    .zip(weights)
+                    .map(|(input, weight)| input * weight)
+                    .sum::<f32>();
+            output += sum.tanh() * output_weights[neuron];
+        }
+        output
+    }
+
+    pub fn should_flap(&self, observation: &Observation) -> bool {
+        self.output(observation) > 0.
+    }
+
+    //copy with every weight having a chance of getting nudged by gaussian noise
+    pub fn mutate(&self, rng: &mut Rng, rate: f32, strength: f32) -> Self {
+        Network {
+            weights: self
+                .weights
+                .iter()
+                .map(|weight| {
+                    if rng.range(0., 1.) < rate {
+                        weight + rng.normal() * strength
+                    } else {
+                        *weight
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    //saved as a header line followed by one weight per line
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut text = String::from(HEADER);
+        for weight in self.weights.iter() {
+            text.push('\n');
+            text.push_str(&weight.to_string());
+        }
+        text.push('\n');
+        fs::write(path, text)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let mut lines = text.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a flappybird genome file",
+            ));
+        }
+        let weights = lines
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                line.trim()
+                    .parse::<f32>()
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+            })
+            .collect::<io::Result<Vec<f32>>>()?;
+        Network::from_weights(weights)
+    }
+}
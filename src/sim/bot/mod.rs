@@ -0,0 +1,13 @@
+//The built in autopilot, it only looks at the same observation a trained controller gets
+use super::env::Observation;
+
+//aim a bit under the middle of the gap as every flap carries the bird upwards
+const AIM_BELOW_GAP: f32 = 40.;
+//seconds ahead we guess where the bird will be if it doesn't flap
+const LOOKAHEAD: f32 = 0.05;
+
+//decides whether to flap from the bird's vertical velocity and the next gap
+pub fn should_flap(observation: &Observation) -> bool {
+    let target = observation.gaps[0].dy - AIM_BELOW_GAP;
+    observation.velocity * LOOKAHEAD < target
+}
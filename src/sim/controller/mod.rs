@@ -0,0 +1,83 @@
+//Anything that can fly a bird in the headless simulation (built in bot, trained network or a replay)
+//and a helper to play a whole run with one
+use super::{bot, env::Observation, replay::Replay, DeathCause, Rules, World};
+use crate::network::Network;
+
+pub trait Controller {
+    fn should_flap(&mut self, world: &World, bird: usize) -> bool;
+}
+
+//the built in autopilot
+pub struct Autopilot;
+
+impl Controller for Autopilot {
+    fn should_flap(&mut self, world: &World, bird: usize) -> bool {
+        bot::should_flap(&Observation::new(world, bird))
+    }
+}
+
+impl Controller for Network {
+    fn should_flap(&mut self, world: &World, bird: usize) -> bool {
+        Network::should_flap(self, &Observation::new(world, bird))
+    }
+}
+
+//flaps on exactly the ticks a replay did, whatever the course looks like
+pub struct ReplayController {
+    flaps: Vec<u64>,
+    next: usize,
+}
+
+impl ReplayController {
+    pub fn new(replay: &Replay) -> Self {
+        ReplayController {
+            flaps: replay.flaps.clone(),
+            next: 0,
+        }
+    }
+}
+
+impl Controller for ReplayController {
+    fn should_flap(&mut self, world: &World, _bird: usize) -> bool {
+        while self
+            .flaps
+            .get(self.next)
+            .is_some_and(|tick| *tick < world.tick())
+        {
+            self.next += 1;
+        }
+        if self.flaps.get(self.next) == Some(&world.tick()) {
+            self.next += 1;
+            return true;
+        }
+        false
+    }
+}
+
+//how a single run went
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RunResult {
+    pub seed: u64,
+    pub score: u32,
+    pub ticks: u64,
+    //None if the run hit the tick limit with the bird still alive
+    pub death: Option<DeathCause>,
+}
+
+//plays one bird from the start until it dies or max_ticks pass
+pub fn play(rules: Rules, seed: u64, controller: &mut dyn Controller, max_ticks: u64) -> RunResult {
+    let mut world = World::new(rules, seed, 1);
+    while world.birds()[0].alive() && world.tick() < max_ticks {
+        if controller.should_flap(&world, 0) {
+            world.flap(0);
+        }
+        world.step();
+    }
+    let bird = &world.birds()[0];
+    RunResult {
+        seed,
+        score: bird.score,
+        ticks: world.tick(),
+        death: bird.death,
+    }
+}
@@ -0,0 +1,113 @@
+//Step/reset environment over the headless simulation for training and evaluating controllers
+//one bird, no window, no bevy, just numbers in and numbers out
+use super::{Rules, World};
+
+//reward for every tick the bird stays alive
+pub const SURVIVAL_REWARD: f32 = 0.01;
+//reward for every point scored
+pub const SCORE_REWARD: f32 = 1.;
+//reward on the tick the bird dies
+pub const DEATH_REWARD: f32 = -1.;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Idle,
+    Flap,
+}
+
+//a gap relative to the bird, dx is how far ahead it is and dy how far above
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GapObservation {
+    pub dx: f32,
+    pub dy: f32,
+}
+
+//what a controller gets to see each tick
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Observation {
+    pub height: f32,
+    pub velocity: f32,
+    //the next two gaps the bird hasn't got through yet, closest first
+    pub gaps: [GapObservation; 2],
+}
+
+impl Observation {
+    pub fn new(world: &World, bird: usize) -> Self {
+        let rules = &world.rules;
+        let height = world.birds()[bird].y;
+        //when there isn't a pipe yet pretend one is waiting at the spawn point in the middle of the screen
+        let mut gaps = [GapObservation {
+            dx: rules.spawn_x - rules.bird_x,
+            dy: -height,
+        }; 2];
+        for (gap, pipe) in gaps.iter_mut().zip(world.upcoming_pipes()) {
+            *gap = GapObservation {
+                dx: pipe.x - rules.bird_x,
+                dy: pipe.gap_y - height,
+            };
+        }
+        Observation {
+            height,
+            velocity: world.birds()[bird].velocity,
+            gaps,
+        }
+    }
+
+    //flattened for feeding into a network, roughly scaled to -1..1
+    pub fn to_inputs(&self) -> [f32; 6] {
+        [
+            self.height / 540.,
+            self.velocity / 1000.,
+            self.gaps[0].dx / 1600.,
+            self.gaps[0].dy / 540.,
+            self.gaps[1].dx / 1600.,
+            self.gaps[1].dy / 540.,
+        ]
+    }
+}
+
+pub struct Env {
+    world: World,
+}
+
+impl Env {
+    pub fn new(rules: Rules) -> Self {
+        Env {
+            world: World::new(rules, 0, 1),
+        }
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.world.reset(seed);
+        Observation::new(&self.world, 0)
+    }
+
+    //returns the new observation, the reward for this step and whether the bird is dead
+    pub fn step(&mut self, action: Action) -> (Observation, f32, bool) {
+        if action == Action::Flap {
+            self.world.flap(0);
+        }
+        let score = self.world.birds()[0].score;
+        let was_alive = self.world.birds()[0].alive();
+        self.world.step();
+
+        let bird = &self.world.birds()[0];
+        let mut reward = (bird.score - score) as f32 * SCORE_REWARD;
+        if bird.alive() {
+            reward += SURVIVAL_REWARD;
+        } else if was_alive {
+            reward += DEATH_REWARD;
+        }
+        (Observation::new(&self.world, 0), reward, !bird.alive())
+    }
+}
+
+impl Default for Env {
+    fn default() -> Self {
+        Env::new(Rules::default())
+    }
+}
@@ -0,0 +1,262 @@
+//convex hitboxes traced from the outline of a sprite, for when the bird and pipes should be the shape
+//they're drawn instead of the circle and boxes in the rules
+//points go x right and y up from the middle of whatever the hull is attached to, anticlockwise
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Hull(pub Vec<[f32; 2]>);
+
+//shapes that replace the bird's circle and the pipes' boxes in a world
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hitboxes {
+    pub bird: Hull,
+    //the top pipe, the bottom one is the same turned upside down like the sprite
+    pub pipe: Hull,
+}
+
+impl Hull {
+    //wraps every pixel at least threshold opaque, alpha is one byte a pixel going across then down
+    //inset pulls every edge in by that many pixels so grazing the very edge doesn't count
+    pub fn from_alpha(
+        width: u32,
+        height: u32,
+        alpha: &[u8],
+        threshold: u8,
+        inset: f32,
+    ) -> Result<Self, String> {
+        if alpha.len() != (width * height) as usize {
+            return Err(format!(
+                "expected {} alpha values for {}x{} but got {}",
+                width * height,
+                width,
+                height,
+                alpha.len()
+            ));
+        }
+        let (half_width, half_height) = (width as f32 / 2., height as f32 / 2.);
+        let mut corners = Vec::new();
+        for (y, row) in alpha.chunks(width as usize).enumerate() {
+            //only the ends of each row can be on the outline
+            let solid = |x: &usize| row[*x] >= threshold;
+            let (Some(left), Some(right)) =
+                ((0..row.len()).find(solid), (0..row.len()).rev().find(solid))
+            else {
+                continue;
+            };
+            for x in [left, right + 1] {
+                for y in [y, y + 1] {
+                    corners.push([x as f32 - half_width, half_height - y as f32]);
+                }
+            }
+        }
+        if corners.is_empty() {
+            return Err(format!("nothing is at least {} opaque", threshold));
+        }
+        let hull = Hull(convex_hull(corners));
+        if inset > 0. {
+            hull.inset(inset)
+                .ok_or(format!("an inset of {} leaves nothing", inset))
+        } else {
+            Ok(hull)
+        }
+    }
+
+    //every edge moved in by the same distance, none if that would turn it inside out
+    pub fn inset(&self, by: f32) -> Option<Self> {
+        let points = &self.0;
+        let count = points.len();
+        if count < 3 {
+            return None;
+        }
+        let normal = |from: [f32; 2], to: [f32; 2]| {
+            let (x, y) = (to[0] - from[0], to[1] - from[1]);
+            let length = (x * x + y * y).sqrt();
+            [-y / length, x / length]
+        };
+        let moved: Vec<[f32; 2]> = (0..count)
+            .map(|i| {
+                let (before, point, after) = (
+                    points[(i + count - 1) % count],
+                    points[i],
+                    points[(i + 1) % count],
+                );
+                let (a, b) = (normal(before, point), normal(point, after));
+                //the corner slides along both edges' normals at once
+                let scale = by / (1. + a[0] * b[0] + a[1] * b[1]);
+                [
+                    point[0] + (a[0] + b[0]) * scale,
+                    point[1] + (a[1] + b[1]) * scale,
+                ]
+            })
+            .collect();
+        let flipped = (0..count).any(|i| {
+            let j = (i + 1) % count;
+            let old = [points[j][0] - points[i][0], points[j][1] - points[i][1]];
+            let new = [moved[j][0] - moved[i][0], moved[j][1] - moved[i][1]];
+            old[0] * new[0] + old[1] * new[1] <= 0.
+        });
+        (!flipped).then_some(Hull(moved))
+    }
+
+    pub fn scaled(&self, x: f32, y: f32) -> Self {
+        Hull(
+            self.0
+                .iter()
+                .map(|point| [point[0] * x, point[1] * y])
+                .collect(),
+        )
+    }
+
+    //moved so what was at this point is the middle
+    pub fn offset(&self, x: f32, y: f32) -> Self {
+        Hull(
+            self.0
+                .iter()
+                .map(|point| [point[0] - x, point[1] - y])
+                .collect(),
+        )
+    }
+
+    //turned half way round
+    pub fn flipped(&self) -> Self {
+        self.scaled(-1., -1.)
+    }
+
+    //turned anticlockwise about the middle
+    pub fn rotated(&self, degrees: f32) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Hull(
+            self.0
+                .iter()
+                .map(|point| {
+                    [
+                        point[0] * cos - point[1] * sin,
+                        point[0] * sin + point[1] * cos,
+                    ]
+                })
+                .collect(),
+        )
+    }
+
+    //whether the two hulls overlap with this one at at and the other at other_at
+    //separating axis test, if there's a gap along any edge's normal they don't touch
+    pub fn overlaps(&self, at: (f32, f32), other: &Hull, other_at: (f32, f32)) -> bool {
+        let (offset_x, offset_y) = (other_at.0 - at.0, other_at.1 - at.1);
+        let project = |hull: &Hull, axis: [f32; 2], shift: (f32, f32)| {
+            hull.0
+                .iter()
+                .fold((f32::MAX, f32::MIN), |(min, max), point| {
+                    let along = (point[0] + shift.0) * axis[0] + (point[1] + shift.1) * axis[1];
+                    (min.min(along), max.max(along))
+                })
+        };
+        [self, other].into_iter().all(|hull| {
+            let count = hull.0.len();
+            (0..count).all(|i| {
+                let (from, to) = (hull.0[i], hull.0[(i + 1) % count]);
+                let axis = [from[1] - to[1], to[0] - from[0]];
+                let (min, max) = project(self, axis, (0., 0.));
+                let (other_min, other_max) = project(other, axis, (offset_x, offset_y));
+                min < other_max && other_min < max
+            })
+        })
+    }
+}
+
+//monotone chain, anticlockwise without any points in the middle of an edge
+fn convex_hull(mut points: Vec<[f32; 2]>) -> Vec<[f32; 2]> {
+    points.sort_by(|a, b| a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1])));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    let cross = |o: [f32; 2], a: [f32; 2], b: [f32; 2]| {
+        (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+    };
+    let mut hull: Vec<[f32; 2]> = Vec::new();
+    for pass in [points.clone(), points.into_iter().rev().collect()] {
+        let start = hull.len();
+        for point in pass {
+            while hull.len() >= start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0.
+            {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        //the last point is the first of the next half
+        hull.pop();
+    }
+    hull
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(half: f32) -> Hull {
+        Hull(vec![
+            [-half, -half],
+            [half, -half],
+            [half, half],
+            [-half, half],
+        ])
+    }
+
+    #[test]
+    fn filled_rectangle_traces_its_corners() {
+        let hull = Hull::from_alpha(4, 2, &[255; 8], 128, 0.).unwrap();
+        assert_eq!(hull, Hull(vec![[-2., -1.], [2., -1.], [2., 1.], [-2., 1.]]));
+    }
+
+    #[test]
+    fn l_shape_traces_around_the_notch() {
+        #[rustfmt::skip]
+        let alpha = [
+            255, 0, 0,
+            255, 0, 0,
+            255, 255, 255,
+        ];
+        let hull = Hull::from_alpha(3, 3, &alpha, 128, 0.).unwrap();
+        assert_eq!(
+            hull,
+            Hull(vec![
+                [-1.5, -1.5],
+                [1.5, -1.5],
+                [1.5, -0.5],
+                [-0.5, 1.5],
+                [-1.5, 1.5],
+            ])
+        );
+    }
+
+    #[test]
+    fn inset_shrinks_every_edge() {
+        assert_eq!(square(2.).inset(1.), Some(square(1.)));
+    }
+
+    #[test]
+    fn inset_collapses_to_nothing() {
+        assert_eq!(square(2.).inset(2.), None);
+        assert_eq!(square(2.).inset(3.), None);
+        assert!(Hull::from_alpha(4, 4, &[255; 16], 128, 2.).is_err());
+    }
+
+    #[test]
+    fn touching_and_separated_hulls_dont_overlap() {
+        let hull = square(1.);
+        assert!(!hull.overlaps((0., 0.), &hull, (2., 0.)));
+        assert!(!hull.overlaps((0., 0.), &hull, (3., 1.)));
+        assert!(hull.overlaps((0., 0.), &hull, (1.9, 0.)));
+    }
+
+    #[test]
+    fn rotated_hulls_overlap_by_their_corners() {
+        let hull = square(1.);
+        let diamond = hull.rotated(45.);
+        //the diamond's corner reaches about 1.41 out instead of 1
+        assert!(!hull.overlaps((0., 0.), &hull, (2.2, 0.)));
+        assert!(hull.overlaps((0., 0.), &diamond, (2.2, 0.)));
+        assert!(!hull.overlaps((0., 0.), &diamond, (2.5, 0.)));
+    }
+}
@@ -0,0 +1,678 @@
+//Headless core of the game
+//All the gameplay rules live in here (gravity, jumping, pipe spawning, collisions and scoring)
+//so the bevy game, the autopilot and any tools all play the exact same game.
+//It runs at a fixed tick and only uses its own seeded rng so a seed and a list of flaps always plays out the same
+pub mod bot;
+pub mod controller;
+pub mod env;
+pub mod hitbox;
+pub mod replay;
+pub mod verify;
+pub mod weather;
+
+use hitbox::{Hitboxes, Hull};
+use serde::{Deserialize, Serialize};
+use weather::Forecast;
+
+//how many times a second the simulation steps
+pub const TICK_RATE: u32 = 60;
+//seconds that pass every step
+pub const DT: f32 = 1. / TICK_RATE as f32;
+
+//every number that decides how the game plays
+//the defaults are what the game used when it ran on rapier
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rules {
+    //rapier's gravity of 98.1 times the bird's gravity scale of 10
+    pub gravity: f32,
+    //vertical velocity the bird is set to when it flaps
+    pub jump_velocity: f32,
+    pub bird_x: f32,
+    //the sprite is 30 wide but the hitbox is 5 smaller so its more forgiving
+    pub bird_radius: f32,
+    pub pipe_speed: f32,
+    //ticks between pipes spawning
+    pub pipe_interval: u32,
+    pub spawn_x: f32,
+    //pipes past this are removed
+    pub despawn_x: f32,
+    //furthest the centre of a gap can be from the middle of the screen
+    pub max_gap_height: f32,
+    //distance from the centre of a gap to the pipes
+    pub gap_half_height: f32,
+    //the pipe sprite is scaled by 0.4 so its (230, 1090) collider ends up this size
+    pub pipe_half_width: f32,
+    pub pipe_half_height: f32,
+    //how much the pipes slow down every tick once every bird is dead
+    pub pipe_slow_down: f32,
+    //top and bottom of the screen
+    pub ceiling: f32,
+    pub floor: f32,
+    //whether flying off the top or bottom of the screen kills the bird
+    //the rapier version let the bird fall forever, this is on so every run ends
+    pub solid_edges: bool,
+    //weather that changes through the run, see weather
+    pub weather: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            gravity: 981.,
+            jump_velocity: 425.,
+            bird_x: -400.,
+            bird_radius: 25.,
+            pipe_speed: 200.,
+            pipe_interval: 150,
+            spawn_x: 1200.,
+            despawn_x: -1200.,
+            max_gap_height: 375.,
+            gap_half_height: 152.,
+            pipe_half_width: 92.,
+            pipe_half_height: 436.,
+            pipe_slow_down: 3.,
+            ceiling: 540.,
+            floor: -540.,
+            solid_edges: true,
+            weather: false,
+        }
+    }
+}
+
+//presets that tweak the gap size and how fast the pipes come at you
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn rules(self) -> Rules {
+        let normal = Rules::default();
+        match self {
+            Difficulty::Easy => Rules {
+                gap_half_height: 190.,
+                pipe_speed: 170.,
+                pipe_interval: 170,
+                ..normal
+            },
+            Difficulty::Normal => normal,
+            Difficulty::Hard => Rules {
+                gap_half_height: 125.,
+                pipe_speed: 240.,
+                pipe_interval: 130,
+                ..normal
+            },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.name() == name)
+    }
+}
+
+//optional twists on top of a difficulty, any mix of them can be on at once
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Mutators(u8);
+
+impl Mutators {
+    pub const NONE: Mutators = Mutators(0);
+    //floatier bird, falls slower and flaps a bit softer
+    pub const LOW_GRAVITY: Mutators = Mutators(1);
+    pub const FAST_PIPES: Mutators = Mutators(1 << 1);
+    pub const NARROW_GAPS: Mutators = Mutators(1 << 2);
+    //rain, wind and fog come and go
+    pub const WEATHER: Mutators = Mutators(1 << 3);
+    pub const ALL: [Mutators; 4] = [
+        Mutators::LOW_GRAVITY,
+        Mutators::FAST_PIPES,
+        Mutators::NARROW_GAPS,
+        Mutators::WEATHER,
+    ];
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    //every mutator on at once
+    pub fn all() -> Self {
+        Mutators::ALL
+            .into_iter()
+            .fold(Mutators::NONE, |all, mutator| all.with(mutator))
+    }
+
+    //none if it has bits for mutators that don't exist
+    pub fn from_bits(bits: u8) -> Option<Self> {
+        (bits & !Mutators::all().0 == 0).then_some(Mutators(bits))
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(self, other: Mutators) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn with(self, other: Mutators) -> Self {
+        Mutators(self.0 | other.0)
+    }
+
+    pub fn rules(self, rules: Rules) -> Rules {
+        let mut rules = rules;
+        if self.contains(Mutators::LOW_GRAVITY) {
+            rules.gravity *= 0.6;
+            rules.jump_velocity *= 0.8;
+        }
+        if self.contains(Mutators::FAST_PIPES) {
+            rules.pipe_speed *= 1.5;
+        }
+        if self.contains(Mutators::NARROW_GAPS) {
+            rules.gap_half_height *= 0.8;
+        }
+        if self.contains(Mutators::WEATHER) {
+            rules.weather = true;
+        }
+        rules
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Mutators::LOW_GRAVITY => "low-gravity",
+            Mutators::FAST_PIPES => "fast-pipes",
+            Mutators::NARROW_GAPS => "narrow-gaps",
+            Mutators::WEATHER => "weather",
+            _ => "mixed",
+        }
+    }
+
+    //the names of every mutator that's on
+    pub fn names(self) -> Vec<&'static str> {
+        Mutators::ALL
+            .into_iter()
+            .filter(|mutator| self.contains(*mutator))
+            .map(Mutators::name)
+            .collect()
+    }
+
+    //a comma or space separated list of names like "low-gravity,fast-pipes"
+    pub fn from_names(names: &str) -> Result<Self, String> {
+        names
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|name| !name.is_empty())
+            .try_fold(Mutators::NONE, |mutators, name| {
+                Mutators::ALL
+                    .into_iter()
+                    .find(|mutator| mutator.name() == name)
+                    .map(|mutator| mutators.with(mutator))
+                    .ok_or(format!("unknown mutator {}", name))
+            })
+    }
+}
+
+//small splitmix64 generator
+//rand's generators aren't promised to give the same numbers between versions so we use our own
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    //uniform float in min..max
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        let unit = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+        min + (max - min) * unit
+    }
+
+    //normally distributed float with a mean of 0 and a standard deviation of 1
+    pub fn normal(&mut self) -> f32 {
+        let u1 = self.range(f32::EPSILON, 1.);
+        let u2 = self.range(0., 1.);
+        (-2. * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeathCause {
+    Pipe,
+    Ground,
+    Ceiling,
+}
+
+#[derive(Clone, Debug)]
+pub struct Bird {
+    pub y: f32,
+    pub velocity: f32,
+    pub score: u32,
+    pub death: Option<DeathCause>,
+    //tick the bird died on, lets birds that scored the same be ranked by who lasted longer
+    pub died_at: Option<u64>,
+    //set by flap and used up on the next step
+    flap: bool,
+    //id of the next pipe this bird can score from so every gap only counts once
+    next_pipe: u32,
+}
+
+impl Bird {
+    fn new() -> Self {
+        Bird {
+            y: 0.,
+            velocity: 0.,
+            score: 0,
+            death: None,
+            died_at: None,
+            flap: false,
+            next_pipe: 0,
+        }
+    }
+
+    pub fn alive(&self) -> bool {
+        self.death.is_none()
+    }
+
+    //degrees the bird is tipped, nose up going up and down to straight down falling
+    //the sprite is drawn at this and a traced hitbox turns with it
+    pub fn tilt(&self) -> f32 {
+        (self.velocity / 10.).clamp(-90., 45.)
+    }
+}
+
+//a top and bottom pipe with the gap between them
+#[derive(Clone, Debug)]
+pub struct Pipe {
+    pub id: u32,
+    pub x: f32,
+    pub gap_y: f32,
+}
+
+//things that happened during a step, the game uses these to spawn sprites and update the score
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    Flapped { bird: usize },
+    Scored { bird: usize },
+    Died { bird: usize, cause: DeathCause },
+    PipeSpawned { id: u32 },
+    PipeRemoved { id: u32 },
+}
+
+#[derive(Clone, Debug)]
+pub struct World {
+    pub rules: Rules,
+    seed: u64,
+    rng: Rng,
+    tick: u64,
+    birds: Vec<Bird>,
+    pipes: Vec<Pipe>,
+    pipe_speed: f32,
+    //ticks until the next pipe spawns
+    spawn_timer: u32,
+    next_pipe_id: u32,
+    //only there when the rules have weather on
+    forecast: Option<Forecast>,
+    //shapes traced from the sprites, the circle and boxes in the rules are used without them
+    //along with the bottom pipe, turned round once here instead of on every step
+    hitboxes: Option<(Hitboxes, Hull)>,
+    events: Vec<Event>,
+}
+
+impl World {
+    pub fn new(rules: Rules, seed: u64, birds: usize) -> Self {
+        World {
+            rules,
+            seed,
+            rng: Rng::new(seed),
+            tick: 0,
+            birds: (0..birds).map(|_| Bird::new()).collect(),
+            pipes: Vec::new(),
+            pipe_speed: rules.pipe_speed,
+            spawn_timer: 0,
+            next_pipe_id: 0,
+            forecast: rules.weather.then(|| Forecast::new(seed)),
+            hitboxes: None,
+            events: Vec::new(),
+        }
+    }
+
+    //replays only have the rules so a world with these won't play back the same
+    pub fn set_hitboxes(&mut self, hitboxes: Option<Hitboxes>) {
+        self.hitboxes = hitboxes.map(|hitboxes| {
+            let bottom = hitboxes.pipe.flipped();
+            (hitboxes, bottom)
+        });
+    }
+
+    //starts over on a new seed with the same rules, hitboxes and number of birds, use new to change those
+    pub fn reset(&mut self, seed: u64) {
+        let hitboxes = self.hitboxes.take();
+        *self = World::new(self.rules, seed, self.birds.len());
+        self.hitboxes = hitboxes;
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn birds(&self) -> &[Bird] {
+        &self.birds
+    }
+
+    pub fn pipes(&self) -> &[Pipe] {
+        &self.pipes
+    }
+
+    pub fn pipe(&self, id: u32) -> Option<&Pipe> {
+        self.pipes.iter().find(|pipe| pipe.id == id)
+    }
+
+    //how fast the pipes are coming at the birds right now, wind included
+    pub fn pipe_speed(&self) -> f32 {
+        self.pipe_speed + self.headwind()
+    }
+
+    pub fn forecast(&self) -> Option<&Forecast> {
+        self.forecast.as_ref()
+    }
+
+    pub fn hitboxes(&self) -> Option<&Hitboxes> {
+        self.hitboxes.as_ref().map(|(hitboxes, _)| hitboxes)
+    }
+
+    //wind only pushes the pipes along while someone's still flying
+    fn headwind(&self) -> f32 {
+        match &self.forecast {
+            Some(forecast) if !self.all_dead() => forecast.wind().0,
+            _ => 0.,
+        }
+    }
+
+    //bird indices from first to last place, most points first then whoever stayed alive longer
+    pub fn standings(&self) -> Vec<usize> {
+        let mut standings: Vec<usize> = (0..self.birds.len()).collect();
+        standings.sort_by_key(|index| {
+            let bird = &self.birds[*index];
+            (
+                std::cmp::Reverse(bird.score),
+                std::cmp::Reverse(bird.died_at.unwrap_or(u64::MAX)),
+            )
+        });
+        standings
+    }
+
+    pub fn all_dead(&self) -> bool {
+        self.birds.iter().all(|bird| !bird.alive())
+    }
+
+    //pipes the bird still has to get through, closest first
+    pub fn upcoming_pipes(&self) -> impl Iterator<Item = &Pipe> {
+        let behind = self.rules.bird_x - self.rules.pipe_half_width - self.rules.bird_radius;
+        self.pipes.iter().filter(move |pipe| pipe.x > behind)
+    }
+
+    //asks the bird to flap on the next step, does nothing if its dead
+    pub fn flap(&mut self, bird: usize) {
+        if let Some(bird) = self.birds.get_mut(bird) {
+            bird.flap = bird.alive();
+        }
+    }
+
+    //moves everything on by one tick and returns what happened
+    pub fn step(&mut self) -> &[Event] {
+        self.events.clear();
+        let rules = self.rules;
+        if let Some(forecast) = self.forecast.as_mut() {
+            forecast.step();
+        }
+        let updraft = self
+            .forecast
+            .as_ref()
+            .map_or(0., |forecast| forecast.wind().1);
+
+        for (index, bird) in self.birds.iter_mut().enumerate() {
+            if bird.flap {
+                bird.flap = false;
+                bird.velocity = rules.jump_velocity;
+                self.events.push(Event::Flapped { bird: index });
+            }
+            bird.velocity -= rules.gravity * DT;
+            if bird.alive() {
+                bird.velocity += updraft * DT;
+            }
+            bird.y += bird.velocity * DT;
+        }
+
+        if self.all_dead() {
+            self.pipe_speed = (self.pipe_speed - rules.pipe_slow_down).max(0.);
+        } else {
+            if self.spawn_timer == 0 {
+                self.spawn_pipe();
+                self.spawn_timer = rules.pipe_interval;
+            }
+            self.spawn_timer -= 1;
+        }
+
+        let speed = self.pipe_speed();
+        for pipe in self.pipes.iter_mut() {
+            pipe.x -= speed * DT;
+        }
+        let events = &mut self.events;
+        self.pipes.retain(|pipe| {
+            let keep = pipe.x >= rules.despawn_x;
+            if !keep {
+                events.push(Event::PipeRemoved { id: pipe.id });
+            }
+            keep
+        });
+
+        for (index, bird) in self.birds.iter_mut().enumerate() {
+            if !bird.alive() {
+                continue;
+            }
+            let hitboxes = self
+                .hitboxes
+                .as_ref()
+                .map(|(hitboxes, bottom)| (hitboxes, bottom));
+            if let Some(cause) = collide(&rules, hitboxes, bird, &self.pipes) {
+                bird.death = Some(cause);
+                bird.died_at = Some(self.tick);
+                self.events.push(Event::Died { bird: index, cause });
+                continue;
+            }
+            for pipe in self.pipes.iter() {
+                if pipe.id >= bird.next_pipe && passes_gap(&rules, bird, pipe) {
+                    bird.next_pipe = pipe.id + 1;
+                    bird.score += 1;
+                    self.events.push(Event::Scored { bird: index });
+                }
+            }
+        }
+
+        self.tick += 1;
+        &self.events
+    }
+
+    fn spawn_pipe(&mut self) {
+        let max = self.rules.max_gap_height;
+        let pipe = Pipe {
+            id: self.next_pipe_id,
+            x: self.rules.spawn_x,
+            gap_y: self.rng.range(-max, max),
+        };
+        self.next_pipe_id += 1;
+        self.events.push(Event::PipeSpawned { id: pipe.id });
+        self.pipes.push(pipe);
+    }
+
+    //hash of everything that affects how the game plays out, two worlds that match will keep matching
+    pub fn checksum(&self) -> u64 {
+        let mut hash: u64 = 0xCBF2_9CE4_8422_2325;
+        let mut mix = |value: u64| {
+            hash ^= value;
+            hash = hash.wrapping_mul(0x0100_0000_01B3);
+        };
+        mix(self.tick);
+        mix(self.rng.0);
+        mix(self.pipe_speed.to_bits() as u64);
+        for bird in self.birds.iter() {
+            mix(bird.y.to_bits() as u64);
+            mix(bird.velocity.to_bits() as u64);
+            mix(bird.score as u64);
+            mix(bird.alive() as u64);
+        }
+        for pipe in self.pipes.iter() {
+            mix(pipe.id as u64);
+            mix(pipe.x.to_bits() as u64);
+        }
+        if let Some(forecast) = &self.forecast {
+            forecast.state().into_iter().for_each(&mut mix);
+        }
+        if let Some((hitboxes, _)) = &self.hitboxes {
+            for point in hitboxes.bird.0.iter().chain(hitboxes.pipe.0.iter()) {
+                mix(point[0].to_bits() as u64);
+                mix(point[1].to_bits() as u64);
+            }
+        }
+        hash
+    }
+}
+
+//what the bird hit this tick if anything
+fn collide(
+    rules: &Rules,
+    hitboxes: Option<(&Hitboxes, &Hull)>,
+    bird: &Bird,
+    pipes: &[Pipe],
+) -> Option<DeathCause> {
+    if rules.solid_edges && bird.y - rules.bird_radius <= rules.floor {
+        return Some(DeathCause::Ground);
+    }
+    if rules.solid_edges && bird.y - rules.bird_radius > rules.ceiling {
+        return Some(DeathCause::Ceiling);
+    }
+    let offset = rules.gap_half_height + rules.pipe_half_height;
+    if let Some((hitboxes, bottom)) = hitboxes {
+        let hull = hitboxes.bird.rotated(bird.tilt());
+        let bird_at = (rules.bird_x, bird.y);
+        let hit = pipes.iter().any(|pipe| {
+            hull.overlaps(bird_at, &hitboxes.pipe, (pipe.x, pipe.gap_y + offset))
+                || hull.overlaps(bird_at, bottom, (pipe.x, pipe.gap_y - offset))
+        });
+        return hit.then_some(DeathCause::Pipe);
+    }
+    for pipe in pipes {
+        for pipe_y in [pipe.gap_y + offset, pipe.gap_y - offset] {
+            if circle_hits_box(
+                (rules.bird_x, bird.y),
+                rules.bird_radius,
+                (pipe.x, pipe_y),
+                (rules.pipe_half_width, rules.pipe_half_height),
+            ) {
+                return Some(DeathCause::Pipe);
+            }
+        }
+    }
+    None
+}
+
+//the gap is a thin sensor down the middle of the pipes like the old PipeGap collider
+fn passes_gap(rules: &Rules, bird: &Bird, pipe: &Pipe) -> bool {
+    circle_hits_box(
+        (rules.bird_x, bird.y),
+        rules.bird_radius,
+        (pipe.x, pipe.gap_y),
+        (0.01, rules.gap_half_height),
+    )
+}
+
+pub fn circle_hits_box(
+    centre: (f32, f32),
+    radius: f32,
+    box_centre: (f32, f32),
+    half: (f32, f32),
+) -> bool {
+    let dx = ((centre.0 - box_centre.0).abs() - half.0).max(0.);
+    let dy = ((centre.1 - box_centre.1).abs() - half.1).max(0.);
+    dx * dx + dy * dy < radius * radius
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //flaps every so often so the bird gets through a few pipes before it dies
+    fn play(seed: u64, ticks: u64) -> Vec<u64> {
+        let mut world = World::new(Rules::default(), seed, 1);
+        (0..ticks)
+            .map(|tick| {
+                if tick % 23 == 0 {
+                    world.flap(0);
+                }
+                world.step();
+                world.checksum()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_and_flaps_match() {
+        assert_eq!(play(42, 2000), play(42, 2000));
+    }
+
+    #[test]
+    fn different_seed_diverges() {
+        assert_ne!(play(42, 2000), play(43, 2000));
+    }
+
+    #[test]
+    fn edges_only_kill_when_solid() {
+        let mut solid = World::new(Rules::default(), 1, 1);
+        let mut open = World::new(
+            Rules {
+                solid_edges: false,
+                ..Rules::default()
+            },
+            1,
+            1,
+        );
+        //falling from the start hits the floor long before any pipe gets there
+        while solid.birds()[0].alive() {
+            assert!(solid.tick() < 600);
+            solid.step();
+            open.step();
+        }
+        assert!(open.birds()[0].alive());
+    }
+}
@@ -0,0 +1,124 @@
+//A run saved as the seed, difficulty, mutators and the ticks the bird flapped on
+//the simulation is deterministic so that's all it takes to play the run back exactly
+use super::{Difficulty, Mutators, Rules};
+use std::{fmt, fs, io, path::Path, str::FromStr};
+
+//first line of a saved replay so we don't try to play some random file
+const HEADER: &str = "flappybird-replay 1";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Replay {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub mutators: Mutators,
+    //ticks the flap went into the simulation on, in order
+    pub flaps: Vec<u64>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, difficulty: Difficulty) -> Self {
+        Replay {
+            seed,
+            difficulty,
+            mutators: Mutators::NONE,
+            flaps: Vec::new(),
+        }
+    }
+
+    pub fn with_mutators(self, mutators: Mutators) -> Self {
+        Replay { mutators, ..self }
+    }
+
+    //the rules the run was played on
+    pub fn rules(&self) -> Rules {
+        self.mutators.rules(self.difficulty.rules())
+    }
+
+    pub fn record(&mut self, tick: u64) {
+        if self.flaps.last() != Some(&tick) {
+            self.flaps.push(tick);
+        }
+    }
+
+    //fnv-1a of the saved text, the same run always gets the same hash
+    pub fn hash(&self) -> u64 {
+        self.to_string()
+            .bytes()
+            .fold(0xCBF2_9CE4_8422_2325u64, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x0100_0000_01B3)
+            })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_string())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        fs::read_to_string(path)?
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        writeln!(f, "seed {}", self.seed)?;
+        writeln!(f, "difficulty {}", self.difficulty.name())?;
+        //left out when there aren't any so plain runs still load in older versions
+        if !self.mutators.is_empty() {
+            writeln!(f, "mutators {}", self.mutators.names().join(" "))?;
+        }
+        write!(f, "flaps")?;
+        for tick in self.flaps.iter() {
+            write!(f, " {}", tick)?;
+        }
+        writeln!(f)
+    }
+}
+
+impl FromStr for Replay {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut lines = text.lines();
+        if lines.next().map(str::trim) != Some(HEADER) {
+            return Err("not a flappybird replay".to_string());
+        }
+        let mut replay = Replay::default();
+        for line in lines {
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("seed") => {
+                    replay.seed = words
+                        .next()
+                        .and_then(|seed| seed.parse().ok())
+                        .ok_or("bad seed")?;
+                }
+                Some("difficulty") => {
+                    replay.difficulty = words
+                        .next()
+                        .and_then(Difficulty::from_name)
+                        .ok_or("bad difficulty")?;
+                }
+                Some("mutators") => {
+                    replay.mutators = Mutators::from_names(&words.collect::<Vec<_>>().join(" "))?;
+                }
+                Some("flaps") => {
+                    replay.flaps = words
+                        .map(|tick| tick.parse().map_err(|_| format!("bad flap tick {}", tick)))
+                        .collect::<Result<_, _>>()?;
+                    if replay.flaps.windows(2).any(|pair| pair[0] >= pair[1]) {
+                        return Err("flap ticks are out of order".to_string());
+                    }
+                }
+                Some(other) => return Err(format!("unknown line {}", other)),
+                None => (),
+            }
+        }
+        Ok(replay)
+    }
+}
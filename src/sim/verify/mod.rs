@@ -0,0 +1,174 @@
+//Checks a submitted run by playing its replay back on the headless simulation
+//the game itself runs on the same simulation so an honest replay always lands on the same score,
+//anything else has been edited or came from something that isn't the game
+use super::{
+    controller::{play, ReplayController, RunResult},
+    replay::Replay,
+    Rules, TICK_RATE,
+};
+use std::fmt;
+
+//a run is cut off this long after its last flap, the bird has hit the ground well before then
+const GRACE_TICKS: u64 = 10 * TICK_RATE as u64;
+//nobody flaps for a day straight, anything later is junk and would take forever to play back
+const MAX_TICK: u64 = 24 * 60 * 60 * TICK_RATE as u64;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rejection {
+    //the replay plays back to a different score than the one claimed
+    ScoreMismatch { claimed: u32, actual: u32 },
+    //the replay flaps after the bird was already dead, the game never records those
+    FlapAfterDeath { tick: u64, died_at: u64 },
+    //the bird somehow never came down
+    NeverDied,
+    //a flap later than any real run could get to
+    TooLong { tick: u64 },
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::ScoreMismatch { claimed, actual } => write!(
+                f,
+                "claimed a score of {} but the replay scores {}",
+                claimed, actual
+            ),
+            Rejection::FlapAfterDeath { tick, died_at } => write!(
+                f,
+                "flaps on tick {} but the bird died on tick {}",
+                tick, died_at
+            ),
+            Rejection::NeverDied => write!(f, "the bird never died"),
+            Rejection::TooLong { tick } => {
+                write!(f, "flaps on tick {}, far past any real run", tick)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Rejection {}
+
+//plays the replay back and returns how the run went if it matches the claimed score
+pub fn verify(replay: &Replay, claimed_score: u32) -> Result<RunResult, Rejection> {
+    check(replay, replay.rules(), claimed_score)
+}
+
+//the rules are separate so the tests can play on ones a replay can't carry
+fn check(replay: &Replay, rules: Rules, claimed_score: u32) -> Result<RunResult, Rejection> {
+    if let Some(tick) = replay.flaps.iter().find(|tick| **tick > MAX_TICK) {
+        return Err(Rejection::TooLong { tick: *tick });
+    }
+    let max_ticks = replay
+        .flaps
+        .last()
+        .map_or(0, |tick| tick.saturating_add(1))
+        .saturating_add(GRACE_TICKS);
+    let result = play(
+        rules,
+        replay.seed,
+        &mut ReplayController::new(replay),
+        max_ticks,
+    );
+    if result.death.is_none() {
+        return Err(Rejection::NeverDied);
+    }
+    //the bird dies during the step of its last tick
+    let died_at = result.ticks - 1;
+    if let Some(tick) = replay.flaps.iter().find(|tick| **tick > died_at) {
+        return Err(Rejection::FlapAfterDeath {
+            tick: *tick,
+            died_at,
+        });
+    }
+    if result.score != claimed_score {
+        return Err(Rejection::ScoreMismatch {
+            claimed: claimed_score,
+            actual: result.score,
+        });
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::{
+        controller::{Autopilot, Controller},
+        Difficulty, World,
+    };
+
+    //the autopilot's flaps up to a tick, after that the bird is left to fall
+    fn autopilot_replay(seed: u64, stop_at: u64) -> Replay {
+        let mut replay = Replay::new(seed, Difficulty::Normal);
+        let mut world = World::new(replay.rules(), seed, 1);
+        while world.birds()[0].alive() {
+            if world.tick() < stop_at && Autopilot.should_flap(&world, 0) {
+                replay.record(world.tick());
+                world.flap(0);
+            }
+            world.step();
+        }
+        replay
+    }
+
+    #[test]
+    fn honest_replay_passes() {
+        let replay = autopilot_replay(7, 1500);
+        let score = play(
+            replay.rules(),
+            replay.seed,
+            &mut ReplayController::new(&replay),
+            u64::MAX,
+        )
+        .score;
+        assert!(score > 0);
+        let result = verify(&replay, score).unwrap();
+        assert_eq!(result.score, score);
+    }
+
+    #[test]
+    fn wrong_score_is_rejected() {
+        let replay = autopilot_replay(7, 1500);
+        let rejection = verify(&replay, 0).unwrap_err();
+        let Rejection::ScoreMismatch { claimed, actual } = rejection else {
+            panic!("expected a score mismatch, got {:?}", rejection);
+        };
+        assert_eq!(claimed, 0);
+        assert!(actual > 0);
+    }
+
+    #[test]
+    fn flap_after_death_is_rejected() {
+        let mut replay = autopilot_replay(7, 100);
+        let died_at = verify(&replay, 0).unwrap().ticks - 1;
+        replay.record(died_at + 30);
+        assert_eq!(
+            verify(&replay, 0),
+            Err(Rejection::FlapAfterDeath {
+                tick: died_at + 30,
+                died_at,
+            })
+        );
+    }
+
+    #[test]
+    fn bird_that_never_dies_is_rejected() {
+        //without a floor a bird that stops flapping falls under the pipes forever
+        let rules = Rules {
+            solid_edges: false,
+            ..Rules::default()
+        };
+        let replay = Replay::new(7, Difficulty::Normal);
+        assert_eq!(check(&replay, rules, 0), Err(Rejection::NeverDied));
+    }
+
+    #[test]
+    fn flap_far_past_any_run_is_rejected() {
+        let mut replay = autopilot_replay(7, 100);
+        replay.record(u64::MAX);
+        assert_eq!(
+            verify(&replay, 0),
+            Err(Rejection::TooLong { tick: u64::MAX })
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//Weather for runs with the weather mutator on
+//It changes every so often on its own rng seeded from the course seed, so the pipes come out the same
+//as without it and a seed still always plays out the same. Only wind changes how the game plays,
+//rain and fog are up to whatever draws the world
+use super::{Rng, TICK_RATE};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Weather {
+    #[default]
+    Clear,
+    Rain,
+    Wind,
+    Fog,
+}
+
+impl Weather {
+    pub fn name(self) -> &'static str {
+        match self {
+            Weather::Clear => "clear",
+            Weather::Rain => "rain",
+            Weather::Wind => "wind",
+            Weather::Fog => "fog",
+        }
+    }
+}
+
+//every run starts clear for this long so the first few pipes are fair
+const CALM_START: u32 = 10 * TICK_RATE;
+//how long a spell of weather lasts
+const SHORTEST: u32 = 8 * TICK_RATE;
+const LONGEST: u32 = 20 * TICK_RATE;
+//ticks it takes to blow in and die down again
+const TURN: u32 = 2 * TICK_RATE;
+//strongest gust, sideways is added onto the pipe speed and up and down onto the bird's velocity every second
+const MAX_HEADWIND: f32 = 60.;
+const MAX_UPDRAFT: f32 = 180.;
+
+//mixed into the course seed so the weather doesn't follow the pipes
+const SEED_MIX: u64 = 0x5745_4154_4845_5221;
+
+#[derive(Clone, Debug)]
+pub struct Forecast {
+    rng: Rng,
+    weather: Weather,
+    //length of this spell and how far into it we are
+    length: u32,
+    elapsed: u32,
+    //(sideways, up) at full strength, sideways is towards the bird
+    wind: (f32, f32),
+}
+
+impl Forecast {
+    pub fn new(seed: u64) -> Self {
+        Forecast {
+            rng: Rng::new(seed ^ SEED_MIX),
+            weather: Weather::Clear,
+            length: CALM_START,
+            elapsed: 0,
+            wind: (0., 0.),
+        }
+    }
+
+    pub fn weather(&self) -> Weather {
+        self.weather
+    }
+
+    //0 to 1, how strong the weather is as it comes in and dies off
+    pub fn intensity(&self) -> f32 {
+        if self.weather == Weather::Clear {
+            return 0.;
+        }
+        let edge = self.elapsed.min(self.length - self.elapsed) as f32;
+        (edge / TURN as f32).min(1.)
+    }
+
+    //(sideways, up) wind right now, sideways is how much faster the pipes come at the bird
+    pub fn wind(&self) -> (f32, f32) {
+        if self.weather != Weather::Wind {
+            return (0., 0.);
+        }
+        let intensity = self.intensity();
+        (self.wind.0 * intensity, self.wind.1 * intensity)
+    }
+
+    pub fn step(&mut self) {
+        self.elapsed += 1;
+        if self.elapsed < self.length {
+            return;
+        }
+        let roll = self.rng.range(0., 1.);
+        self.weather = match roll {
+            roll if roll < 0.35 => Weather::Clear,
+            roll if roll < 0.6 => Weather::Rain,
+            roll if roll < 0.85 => Weather::Wind,
+            _ => Weather::Fog,
+        };
+        self.length = self.rng.range(SHORTEST as f32, LONGEST as f32) as u32;
+        self.elapsed = 0;
+        self.wind = (
+            self.rng.range(-MAX_HEADWIND, MAX_HEADWIND),
+            self.rng.range(-MAX_UPDRAFT, MAX_UPDRAFT),
+        );
+    }
+
+    //everything that changes how the game plays for the world's checksum
+    pub(super) fn state(&self) -> [u64; 3] {
+        [
+            self.rng.0,
+            self.weather as u64,
+            self.elapsed as u64 | (self.length as u64) << 32,
+        ]
+    }
+}
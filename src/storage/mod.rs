@@ -0,0 +1,29 @@
+//Where the game keeps things between runs (replays, scores and so on)
+//defaults to a save folder next to where the game is run so it travels with the exe and its assets
+use std::{env, path::PathBuf};
+
+pub fn data_dir() -> PathBuf {
+    env::var_os("FLAPPYBIRD_DATA")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("save"))
+}
+
+//the last run the game played, saved every time the bird dies
+pub fn last_replay_path() -> PathBuf {
+    data_dir().join("replays").join("last.replay")
+}
+
+//scores that couldn't be sent to the leaderboard yet
+pub fn leaderboard_queue_path() -> PathBuf {
+    data_dir().join("leaderboard_queue.json")
+}
+
+//best daily challenge score for every day played
+pub fn daily_records_path() -> PathBuf {
+    data_dir().join("daily.json")
+}
+
+//the player's options, see config
+pub fn config_path() -> PathBuf {
+    data_dir().join("config.json")
+}